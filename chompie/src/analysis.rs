@@ -0,0 +1,327 @@
+use crate::file_manager::{FileManager, FileState};
+use std::path::{Path, PathBuf};
+
+/// How `chompie analyze` writes its findings
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Format {
+    /// A summary per file, for reading
+    Text,
+    /// The removable ranges per file, for scripts
+    Json,
+    /// SARIF 2.1.0, for editor and CI annotations
+    Sarif,
+}
+
+const RULE_ID: &str = "removable-code";
+
+/// Which lines of one file the command's output needs
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileAnalysis {
+    /// Relative to the analyzed directory
+    pub path: PathBuf,
+    /// Non-blank lines before chomping
+    pub lines: usize,
+    /// Non-blank lines that could go
+    pub removable_lines: usize,
+    /// Line ranges that could go, 1-based and inclusive; blank lines don't split a range
+    pub removable: Vec<(usize, usize)>,
+    /// Whether the whole file could go
+    pub deleted: bool,
+}
+
+impl FileAnalysis {
+    fn new(path: PathBuf, state: &FileState) -> Self {
+        let mut lines = 0;
+        let mut removable_lines = 0;
+        let mut removable: Vec<(usize, usize)> = Vec::new();
+        let mut open = false;
+        for (i, text) in state.original_lines.iter().enumerate() {
+            if text.trim().is_empty() {
+                continue;
+            }
+            lines += 1;
+            // Lines edited below line level are still needed, just shorter
+            if !state.deleted && !state.blanked_lines.contains(&i) {
+                open = false;
+                continue;
+            }
+            removable_lines += 1;
+            match removable.last_mut() {
+                Some((_, end)) if open => *end = i + 1,
+                _ => removable.push((i + 1, i + 1)),
+            }
+            open = true;
+        }
+        FileAnalysis { path, lines, removable_lines, removable, deleted: state.deleted }
+    }
+
+    pub fn required_lines(&self) -> usize {
+        self.lines - self.removable_lines
+    }
+}
+
+/// Lines found removable by chomping a copy, without applying any of it
+pub struct Analysis {
+    pub command: String,
+    /// The analyzed directory, absolute
+    pub root: PathBuf,
+    /// Sorted by path
+    pub files: Vec<FileAnalysis>,
+}
+
+impl Analysis {
+    /// Compare the chomped files under `root` with their originals
+    pub fn new(command: &str, files: &FileManager, root: &Path, original_root: &Path) -> Self {
+        let mut analyzed: Vec<FileAnalysis> = files
+            .files()
+            .values()
+            .map(|state| {
+                let relative = state.path.strip_prefix(root).unwrap_or(&state.path);
+                FileAnalysis::new(relative.to_path_buf(), state)
+            })
+            .collect();
+        analyzed.sort_by(|a, b| a.path.cmp(&b.path));
+        Analysis { command: command.to_string(), root: original_root.to_path_buf(), files: analyzed }
+    }
+
+    pub fn lines(&self) -> usize {
+        self.files.iter().map(|file| file.lines).sum()
+    }
+
+    pub fn removable_lines(&self) -> usize {
+        self.files.iter().map(|file| file.removable_lines).sum()
+    }
+
+    pub fn render(&self, format: Format) -> String {
+        match format {
+            Format::Text => self.render_text(),
+            Format::Json => self.render_json(),
+            Format::Sarif => self.render_sarif(),
+        }
+    }
+
+    fn render_text(&self) -> String {
+        let mut out = String::from("=== Dead Code Report ===\n");
+        out.push_str(&format!("Command: {}\n\n", self.command));
+        for file in &self.files {
+            if file.deleted {
+                out.push_str(&format!("{}: removable (whole file, {} lines)\n", file.path.display(), file.lines));
+                continue;
+            }
+            if file.removable.is_empty() {
+                out.push_str(&format!("{}: required (all {} lines)\n", file.path.display(), file.lines));
+                continue;
+            }
+            out.push_str(&format!(
+                "{}: {} of {} lines removable, {} required\n",
+                file.path.display(),
+                file.removable_lines,
+                file.lines,
+                file.required_lines()
+            ));
+            for (start, end) in &file.removable {
+                if start == end {
+                    out.push_str(&format!("  line {}\n", start));
+                } else {
+                    out.push_str(&format!("  lines {}-{}\n", start, end));
+                }
+            }
+        }
+
+        let percent = match self.lines() {
+            0 => 0.0,
+            lines => self.removable_lines() as f64 / lines as f64 * 100.0,
+        };
+        out.push_str(&format!(
+            "\nRemovable: {} of {} lines ({:.1}%) in {} of {} files\n",
+            self.removable_lines(),
+            self.lines(),
+            percent,
+            self.files.iter().filter(|file| file.removable_lines > 0).count(),
+            self.files.len()
+        ));
+        out
+    }
+
+    fn render_json(&self) -> String {
+        let files: Vec<String> = self
+            .files
+            .iter()
+            .map(|file| {
+                let ranges: Vec<String> = file
+                    .removable
+                    .iter()
+                    .map(|(start, end)| format!("{{\"start\": {}, \"end\": {}}}", start, end))
+                    .collect();
+                format!(
+                    "    {{\"path\": {}, \"lines\": {}, \"required\": {}, \"removable\": {}, \"deleted\": {}, \"ranges\": [{}]}}",
+                    json_string(&uri(&file.path)),
+                    file.lines,
+                    file.required_lines(),
+                    file.removable_lines,
+                    file.deleted,
+                    ranges.join(", ")
+                )
+            })
+            .collect();
+        format!(
+            "{{\n  \"command\": {},\n  \"root\": {},\n  \"lines\": {},\n  \"removable\": {},\n  \"files\": [\n{}\n  ]\n}}\n",
+            json_string(&self.command),
+            json_string(&self.root.to_string_lossy()),
+            self.lines(),
+            self.removable_lines(),
+            files.join(",\n")
+        )
+    }
+
+    fn render_sarif(&self) -> String {
+        let mut results = Vec::new();
+        for file in &self.files {
+            // A removable file is one range from its first to its last line of text
+            for &(start, end) in &file.removable {
+                let message = match (file.deleted, start == end) {
+                    (true, _) => format!("The whole file can be removed without changing the output of `{}`", self.command),
+                    (false, true) => format!("Line {} can be removed without changing the output of `{}`", start, self.command),
+                    (false, false) => {
+                        format!("Lines {}-{} can be removed without changing the output of `{}`", start, end, self.command)
+                    }
+                };
+                results.push(format!(
+                    "        {{\"ruleId\": \"{}\", \"level\": \"note\", \"message\": {{\"text\": {}}}, \"locations\": [{{\"physicalLocation\": {{\"artifactLocation\": {{\"uri\": {}, \"uriBaseId\": \"SRCROOT\"}}, \"region\": {{\"startLine\": {}, \"endLine\": {}}}}}}}]}}",
+                    RULE_ID,
+                    json_string(&message),
+                    json_string(&uri(&file.path)),
+                    start,
+                    end
+                ));
+            }
+        }
+
+        let mut root = uri(&self.root);
+        if !root.ends_with('/') {
+            root.push('/');
+        }
+        if !root.starts_with('/') {
+            root.insert(0, '/');
+        }
+        format!(
+            concat!(
+                "{{\n",
+                "  \"$schema\": \"https://json.schemastore.org/sarif-2.1.0.json\",\n",
+                "  \"version\": \"2.1.0\",\n",
+                "  \"runs\": [\n",
+                "    {{\n",
+                "      \"tool\": {{\"driver\": {{\"name\": \"chompie\", \"version\": \"{}\", \"rules\": [{{\"id\": \"{}\", ",
+                "\"shortDescription\": {{\"text\": \"Code the command's output doesn't need\"}}}}]}}}},\n",
+                "      \"originalUriBaseIds\": {{\"SRCROOT\": {{\"uri\": {}}}}},\n",
+                "      \"results\": [\n{}\n      ]\n",
+                "    }}\n",
+                "  ]\n",
+                "}}\n"
+            ),
+            env!("CARGO_PKG_VERSION"),
+            RULE_ID,
+            json_string(&format!("file://{}", root)),
+            results.join(",\n")
+        )
+    }
+}
+
+/// A path with forward slashes, as URIs and JSON consumers expect
+fn uri(path: &Path) -> String {
+    path.to_string_lossy().replace('\\', "/")
+}
+
+pub fn json_string(text: &str) -> String {
+    let mut out = String::from("\"");
+    for c in text.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn analysis() -> Analysis {
+        let mut files = FileManager::new();
+        let root = Path::new("/sandbox");
+        files.add_file_from_content(root.join("src/a.py"), "import os\nx = 1\n\ny = 2\nprint(x)\nz = 3".to_string());
+        files.add_file_from_content(root.join("util.py"), "def f():\n    pass".to_string());
+        files.add_file_from_content(root.join("b.py"), "print(2)".to_string());
+        let a = files.get_file_mut(&root.join("src/a.py")).unwrap();
+        a.blank_lines(&[0, 3, 5]);
+        a.replace_line(1, "x=1".to_string());
+        files.get_file_mut(&root.join("util.py")).unwrap().delete();
+        Analysis::new("python main.py", &files, root, Path::new("/home/me/project"))
+    }
+
+    #[test]
+    fn test_classify_lines() {
+        let analysis = analysis();
+        let paths: Vec<&Path> = analysis.files.iter().map(|file| file.path.as_path()).collect();
+        assert_eq!(paths, vec![Path::new("b.py"), Path::new("src/a.py"), Path::new("util.py")]);
+
+        let a = &analysis.files[1];
+        assert_eq!((a.lines, a.removable_lines, a.required_lines()), (5, 3, 2));
+        // The edited line 2 is still required, so it splits 1 from 4, and line 5 splits 4 from 6;
+        // the blank line 3 isn't counted either way
+        assert_eq!(a.removable, vec![(1, 1), (4, 4), (6, 6)]);
+        assert!(analysis.files[0].removable.is_empty());
+        assert!(analysis.files[2].deleted);
+        assert_eq!(analysis.files[2].removable, vec![(1, 2)]);
+        assert_eq!((analysis.lines(), analysis.removable_lines()), (8, 5));
+    }
+
+    #[test]
+    fn test_blank_lines_join_ranges() {
+        let mut state = FileState::new(PathBuf::from("a.py"), "a\nb\n\n\nc\nd".to_string());
+        state.blank_lines(&[0, 1, 4]);
+        assert_eq!(FileAnalysis::new(PathBuf::from("a.py"), &state).removable, vec![(1, 5)]);
+    }
+
+    #[test]
+    fn test_render_text() {
+        let text = analysis().render(Format::Text);
+        assert!(text.starts_with("=== Dead Code Report ===\nCommand: python main.py\n"));
+        assert!(text.contains("b.py: required (all 1 lines)\n"));
+        assert!(text.contains("src/a.py: 3 of 5 lines removable, 2 required\n  line 1\n  line 4\n  line 6\n"));
+        assert!(text.contains("util.py: removable (whole file, 2 lines)\n"));
+        assert!(text.ends_with("Removable: 5 of 8 lines (62.5%) in 2 of 3 files\n"));
+    }
+
+    #[test]
+    fn test_render_json_and_sarif() {
+        let analysis = analysis();
+        let json = analysis.render(Format::Json);
+        assert!(json.contains("\"root\": \"/home/me/project\""));
+        assert!(json.contains(
+            "{\"path\": \"src/a.py\", \"lines\": 5, \"required\": 2, \"removable\": 3, \"deleted\": false, \"ranges\": [{\"start\": 1, \"end\": 1}, {\"start\": 4, \"end\": 4}, {\"start\": 6, \"end\": 6}]}"
+        ));
+        assert_eq!(json.matches('{').count(), json.matches('}').count());
+
+        let sarif = analysis.render(Format::Sarif);
+        assert!(sarif.contains("\"version\": \"2.1.0\""));
+        assert!(sarif.contains("\"SRCROOT\": {\"uri\": \"file:///home/me/project/\"}"));
+        assert_eq!(sarif.matches("\"ruleId\": \"removable-code\"").count(), 4);
+        assert!(sarif.contains("\"The whole file can be removed without changing the output of `python main.py`\""));
+        assert!(sarif.contains("\"uri\": \"util.py\", \"uriBaseId\": \"SRCROOT\"}, \"region\": {\"startLine\": 1, \"endLine\": 2}"));
+        assert_eq!(sarif.matches('[').count(), sarif.matches(']').count());
+    }
+
+    #[test]
+    fn test_json_string() {
+        assert_eq!(json_string("say \"hi\"\n\\"), "\"say \\\"hi\\\"\\n\\\\\"");
+        assert_eq!(json_string("\u{1}"), "\"\\u0001\"");
+    }
+}
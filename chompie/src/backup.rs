@@ -0,0 +1,286 @@
+use crate::git;
+use crate::timestamp;
+use anyhow::{Context, Result};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Where sessions live, relative to the chomped directory
+pub const BACKUP_DIR: &str = ".chompie/backup";
+
+/// Pristine copies of the files a run may modify, so the run can be undone without git
+///
+/// Each run gets a session directory `<directory>/.chompie/backup/<session>` holding a copy of
+/// every file under `files/`, a `manifest` of SHA-256 hashes in `sha256sum` format, and a
+/// `session` journal of `key: value` lines describing the run, where later lines win.
+/// `.chompie` ignores itself for git.
+pub struct Backup {
+    /// The session directory
+    dir: PathBuf,
+    /// The chomped directory the files are restored into
+    directory: PathBuf,
+}
+
+/// A past run, as listed by `chompie sessions`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Session {
+    pub id: String,
+    pub command: String,
+    pub files: usize,
+}
+
+impl Backup {
+    /// Copy `files`, which live under `directory`, into a new session before anything is written
+    pub fn create(directory: &Path, command: &str, files: &[&Path]) -> Result<Backup> {
+        // Absolute, so the session survives a change of working directory
+        let files: Vec<PathBuf> = files.iter().map(|path| git::absolute(path)).collect::<Result<_>>()?;
+        let directory = &git::absolute(directory)?;
+        let root = directory.join(BACKUP_DIR);
+        fs::create_dir_all(&root).with_context(|| format!("Failed to create {:?}", root))?;
+        fs::write(directory.join(".chompie/.gitignore"), "*\n")?;
+
+        // Two runs in the same second get numbered sessions
+        let id = timestamp::now()?;
+        let mut dir = root.join(&id);
+        let mut n = 1;
+        while dir.exists() {
+            n += 1;
+            dir = root.join(format!("{}-{}", id, n));
+        }
+        fs::create_dir(&dir)?;
+
+        let mut manifest = String::new();
+        for path in &files {
+            let relative = path
+                .strip_prefix(directory)
+                .with_context(|| format!("{:?} is outside {:?}", path, directory))?;
+            let content = fs::read(path).with_context(|| format!("Failed to back up {:?}", path))?;
+            let copy = dir.join("files").join(relative);
+            fs::create_dir_all(copy.parent().expect("backup copies are inside the session"))?;
+            fs::write(&copy, &content)?;
+            manifest.push_str(&format!("{}  {}\n", hash(&content), relative.display()));
+        }
+        fs::write(dir.join("manifest"), manifest)?;
+        fs::write(dir.join("session"), format!("command: {}\nfiles: {}\n", command, files.len()))?;
+
+        Ok(Backup { dir, directory: directory.clone() })
+    }
+
+    /// Add `key: value` to the session journal, replacing any earlier value
+    pub fn record(&self, key: &str, value: &str) -> Result<()> {
+        let mut journal = fs::OpenOptions::new().append(true).open(self.dir.join("session"))?;
+        writeln!(journal, "{}: {}", key, value)?;
+        Ok(())
+    }
+
+    /// The latest value recorded for `key`, None if there's none or it was cleared
+    pub fn field(&self, key: &str) -> Option<String> {
+        let journal = fs::read_to_string(self.dir.join("session")).ok()?;
+        field(&journal, key).filter(|value| !value.is_empty())
+    }
+
+    /// Save the command-line arguments of the run, so it can be resumed
+    pub fn save_args(&self, args: &[String]) -> Result<()> {
+        fs::write(self.dir.join("args"), args.join("\0"))?;
+        Ok(())
+    }
+
+    pub fn args(&self) -> Result<Vec<String>> {
+        let args = fs::read_to_string(self.dir.join("args"))
+            .with_context(|| format!("Session {} has no saved arguments to resume with", self.id()))?;
+        Ok(args.split('\0').map(str::to_string).collect())
+    }
+
+    /// Open a session of `directory`, the most recent one if `id` is None
+    pub fn open(directory: &Path, id: Option<&str>) -> Result<Backup> {
+        let directory = &git::absolute(directory)?;
+        let id = match id {
+            Some(id) => id.to_string(),
+            None => {
+                let sessions = Backup::sessions(directory)?;
+                sessions.last().with_context(|| format!("No backups in {:?}", directory.join(BACKUP_DIR)))?.id.clone()
+            }
+        };
+        let dir = directory.join(BACKUP_DIR).join(&id);
+        if !dir.join("manifest").is_file() {
+            anyhow::bail!("No backup session {:?} in {:?}", id, directory.join(BACKUP_DIR));
+        }
+        Ok(Backup { dir, directory: directory.clone() })
+    }
+
+    /// Sessions of `directory`, oldest first
+    pub fn sessions(directory: &Path) -> Result<Vec<Session>> {
+        let root = directory.join(BACKUP_DIR);
+        if !root.is_dir() {
+            return Ok(Vec::new());
+        }
+
+        let mut sessions = Vec::new();
+        for entry in fs::read_dir(&root)? {
+            let path = entry?.path();
+            let Ok(description) = fs::read_to_string(path.join("session")) else {
+                continue;
+            };
+            sessions.push(Session {
+                id: path.file_name().unwrap_or_default().to_string_lossy().to_string(),
+                command: field(&description, "command").unwrap_or_default(),
+                files: field(&description, "files").and_then(|files| files.parse().ok()).unwrap_or(0),
+            });
+        }
+        // Ids are timestamps, numbered when two runs start in the same second
+        sessions.sort_by_key(|session| {
+            let (time, n) = session.id.split_at(session.id.len().min(15));
+            (time.to_string(), n.trim_start_matches('-').parse::<usize>().unwrap_or(1))
+        });
+        Ok(sessions)
+    }
+
+    pub fn id(&self) -> String {
+        self.dir.file_name().unwrap_or_default().to_string_lossy().to_string()
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// The chomped directory
+    pub fn directory(&self) -> &Path {
+        &self.directory
+    }
+
+    /// The backed-up files by path relative to the chomped directory, each checked against
+    /// its hash
+    pub fn files(&self) -> Result<Vec<(PathBuf, Vec<u8>)>> {
+        let manifest = fs::read_to_string(self.dir.join("manifest"))?;
+        let mut files = Vec::new();
+        for line in manifest.lines().filter(|line| !line.is_empty()) {
+            let (expected, relative) = line.split_once("  ").with_context(|| format!("Bad manifest line {:?}", line))?;
+            let content = fs::read(self.dir.join("files").join(relative))
+                .with_context(|| format!("Backup of {} is missing", relative))?;
+            if hash(&content) != expected {
+                anyhow::bail!("Backup of {} is corrupt: its hash doesn't match the manifest", relative);
+            }
+            files.push((PathBuf::from(relative), content));
+        }
+        Ok(files)
+    }
+
+    /// Put every backed-up file back, recreating deleted ones
+    /// Every copy is checked before any file is written.
+    /// Returns the number of files restored.
+    pub fn restore(&self) -> Result<usize> {
+        let files = self.files()?;
+        for (relative, content) in &files {
+            let path = self.directory.join(relative);
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(&path, content).with_context(|| format!("Failed to restore {:?}", path))?;
+        }
+        Ok(files.len())
+    }
+
+    /// Delete the session, and `.chompie` with it once no sessions are left
+    pub fn remove(self) -> Result<()> {
+        fs::remove_dir_all(&self.dir).with_context(|| format!("Failed to remove {:?}", self.dir))?;
+        // Tidy up the directories the first backup created, but only while they're empty
+        let root = self.directory.join(BACKUP_DIR);
+        if fs::read_dir(&root)?.next().is_none() {
+            fs::remove_dir(&root)?;
+            let chompie = self.directory.join(".chompie");
+            let left: Vec<_> = fs::read_dir(&chompie)?.collect::<std::io::Result<_>>()?;
+            if left.iter().all(|entry| entry.file_name() == ".gitignore") {
+                fs::remove_dir_all(&chompie)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The last value of `key` in a session journal
+fn field(journal: &str, key: &str) -> Option<String> {
+    journal
+        .lines()
+        .rev()
+        .find_map(|line| line.strip_prefix(key)?.strip_prefix(": "))
+        .map(str::to_string)
+}
+
+pub fn hash(content: &[u8]) -> String {
+    Sha256::digest(content).iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_hash() {
+        assert_eq!(hash(b"abc"), "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad");
+    }
+
+    #[test]
+    fn test_backup_and_restore() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path();
+        fs::create_dir(dir.join("src")).unwrap();
+        let main = dir.join("src/main.rs");
+        let util = dir.join("util.py");
+        fs::write(&main, "fn main() {}\n").unwrap();
+        fs::write(&util, "x = 1").unwrap();
+
+        let backup = Backup::create(dir, "cargo run", &[&main, &util]).unwrap();
+        let second = Backup::create(dir, "python util.py", &[&util]).unwrap();
+        assert_eq!(fs::read_to_string(dir.join(".chompie/.gitignore")).unwrap(), "*\n");
+        assert_eq!(
+            Backup::sessions(dir).unwrap(),
+            vec![
+                Session { id: backup.id(), command: "cargo run".to_string(), files: 2 },
+                Session { id: second.id(), command: "python util.py".to_string(), files: 1 },
+            ]
+        );
+
+        // Chomped, with a file deleted and its directory gone
+        fs::write(&util, "").unwrap();
+        fs::remove_dir_all(dir.join("src")).unwrap();
+        assert_eq!(Backup::open(dir, Some(&backup.id())).unwrap().restore().unwrap(), 2);
+        assert_eq!(fs::read_to_string(&main).unwrap(), "fn main() {}\n");
+        assert_eq!(fs::read_to_string(&util).unwrap(), "x = 1");
+
+        // The latest session by default, and never from a corrupt copy
+        assert_eq!(Backup::open(dir, None).unwrap().id(), second.id());
+        fs::write(second.dir().join("files/util.py"), "tampered").unwrap();
+        assert!(Backup::open(dir, None).unwrap().restore().is_err());
+        assert!(Backup::open(dir, Some("nope")).is_err());
+    }
+
+    #[test]
+    fn test_session_journal() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path();
+        let backup = Backup::create(dir, "make", &[]).unwrap();
+        backup.save_args(&["-d".to_string(), "src dir".to_string(), "make test".to_string()]).unwrap();
+        assert_eq!(backup.args().unwrap(), vec!["-d", "src dir", "make test"]);
+
+        backup.record("worktree", "/tmp/w").unwrap();
+        assert_eq!(backup.field("worktree"), Some("/tmp/w".to_string()));
+        backup.record("worktree", "").unwrap();
+        assert_eq!(backup.field("worktree"), None);
+        assert_eq!(backup.field("command"), Some("make".to_string()));
+
+        // The last session takes .chompie with it, unless something else lives there
+        let second = Backup::create(dir, "make", &[]).unwrap();
+        backup.remove().unwrap();
+        assert!(dir.join(".chompie").exists());
+        second.remove().unwrap();
+        assert!(!dir.join(".chompie").exists());
+
+        let third = Backup::create(dir, "make", &[]).unwrap();
+        fs::write(dir.join(".chompie/notes"), "keep").unwrap();
+        third.remove().unwrap();
+        assert!(!dir.join(BACKUP_DIR).exists());
+        assert!(dir.join(".chompie/notes").exists());
+    }
+}
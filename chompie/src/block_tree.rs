@@ -0,0 +1,227 @@
+use crate::file_manager::FileState;
+
+/// A region of lines in a file along with the regions nested inside it
+///
+/// Blocks are found from bracket nesting and indentation, so the same tree works for
+/// brace languages (Rust, C, JS, Go, ...) and indentation languages (Python, Ruby bodies).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Block {
+    pub start_line: usize,
+    /// Exclusive
+    pub end_line: usize,
+    pub children: Vec<Block>,
+}
+
+impl Block {
+    /// Build the tree for the current (non-blanked) content of a file
+    /// The root covers the whole file and its children are the top-level items
+    pub fn from_file(state: &FileState) -> Block {
+        let lines: Vec<&str> = (0..state.total_lines()).map(|i| state.line(i)).collect();
+        Block::from_lines(&lines)
+    }
+
+    pub fn from_lines(lines: &[&str]) -> Block {
+        Block {
+            start_line: 0,
+            end_line: lines.len(),
+            children: parse_items(lines, 0, lines.len()),
+        }
+    }
+
+    /// Sibling lists level by level from the root down: the root's children first,
+    /// then all of their children, and so on
+    pub fn levels(&self) -> Vec<Vec<&[Block]>> {
+        let mut levels = Vec::new();
+        let mut current: Vec<&Block> = vec![self];
+
+        while !current.is_empty() {
+            let siblings: Vec<&[Block]> = current
+                .iter()
+                .map(|block| block.children.as_slice())
+                .filter(|children| !children.is_empty())
+                .collect();
+            if siblings.is_empty() {
+                break;
+            }
+            current = siblings.iter().flat_map(|children| children.iter()).collect();
+            levels.push(siblings);
+        }
+
+        levels
+    }
+}
+
+/// Split lines [start, end) into top-level items, each with its nested items
+fn parse_items(lines: &[&str], start: usize, end: usize) -> Vec<Block> {
+    let mut items = Vec::new();
+    let mut i = start;
+
+    while i < end {
+        if lines[i].trim().is_empty() {
+            i += 1;
+            continue;
+        }
+
+        let mut depth = bracket_delta(lines[i]);
+        let item_end;
+        let children;
+
+        if depth > 0 {
+            // Bracketed block: runs until the brackets balance again
+            let mut j = i + 1;
+            while j < end && depth > 0 {
+                depth += bracket_delta(lines[j]);
+                j += 1;
+            }
+            item_end = j;
+            // The last line holds the closing bracket, so it isn't a child
+            let inner_end = if depth <= 0 { item_end - 1 } else { item_end };
+            children = parse_items(lines, i + 1, inner_end.max(i + 1));
+        } else {
+            // Indented block: every following line indented deeper than this one
+            let indent = indentation(lines[i]);
+            let mut last = i;
+            let mut j = i + 1;
+            while j < end {
+                if lines[j].trim().is_empty() {
+                    j += 1;
+                    continue;
+                }
+                if indentation(lines[j]) <= indent {
+                    break;
+                }
+                last = j;
+                j += 1;
+            }
+            item_end = last + 1;
+            children = parse_items(lines, i + 1, item_end);
+        }
+
+        items.push(Block {
+            start_line: i,
+            end_line: item_end,
+            children,
+        });
+        i = item_end;
+    }
+
+    items
+}
+
+fn indentation(line: &str) -> usize {
+    line.chars()
+        .take_while(|c| c.is_whitespace())
+        .map(|c| if c == '\t' { 4 } else { 1 })
+        .sum()
+}
+
+/// Opening minus closing brackets on a line, ignoring strings, char literals and `//` comments
+pub fn bracket_delta(line: &str) -> i32 {
+    let chars: Vec<char> = line.chars().collect();
+    let mut delta = 0;
+    let mut i = 0;
+
+    while i < chars.len() {
+        match chars[i] {
+            '"' => {
+                i += 1;
+                while i < chars.len() && chars[i] != '"' {
+                    if chars[i] == '\\' {
+                        i += 1;
+                    }
+                    i += 1;
+                }
+            }
+            // Char literals like '{' or '\n', but not lifetimes like 'a
+            '\'' if chars.get(i + 2) == Some(&'\'') => i += 2,
+            '\'' if chars.get(i + 1) == Some(&'\\') && chars.get(i + 3) == Some(&'\'') => i += 3,
+            '/' if chars.get(i + 1) == Some(&'/') => break,
+            '{' | '(' | '[' => delta += 1,
+            '}' | ')' | ']' => delta -= 1,
+            _ => {}
+        }
+        i += 1;
+    }
+
+    delta
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn span(block: &Block) -> (usize, usize) {
+        (block.start_line, block.end_line)
+    }
+
+    #[test]
+    fn test_brace_blocks() {
+        let lines = [
+            "fn main() {",
+            "    let x = 1;",
+            "    if x > 0 {",
+            "        println!(\"{}\", x);",
+            "    }",
+            "}",
+            "",
+            "fn other() {}",
+        ];
+        let root = Block::from_lines(&lines);
+
+        assert_eq!(root.children.len(), 2);
+        assert_eq!(span(&root.children[0]), (0, 6));
+        assert_eq!(span(&root.children[1]), (7, 8));
+
+        let body = &root.children[0].children;
+        assert_eq!(body.len(), 2);
+        assert_eq!(span(&body[0]), (1, 2));
+        assert_eq!(span(&body[1]), (2, 5));
+        assert_eq!(span(&body[1].children[0]), (3, 4));
+    }
+
+    #[test]
+    fn test_indentation_blocks() {
+        let lines = ["def f():", "    x = 1", "", "    return x", "", "print(f())"];
+        let root = Block::from_lines(&lines);
+
+        assert_eq!(root.children.len(), 2);
+        assert_eq!(span(&root.children[0]), (0, 4));
+        assert_eq!(root.children[0].children.len(), 2);
+        assert_eq!(span(&root.children[1]), (5, 6));
+    }
+
+    #[test]
+    fn test_else_branch_is_nested_item() {
+        let lines = ["if x {", "    a();", "} else {", "    b();", "}"];
+        let root = Block::from_lines(&lines);
+
+        assert_eq!(root.children.len(), 1);
+        assert_eq!(span(&root.children[0]), (0, 5));
+        let inner = &root.children[0].children;
+        assert_eq!(span(&inner[0]), (1, 2));
+        assert_eq!(span(&inner[1]), (2, 4));
+    }
+
+    #[test]
+    fn test_brackets_in_strings_and_comments() {
+        assert_eq!(bracket_delta("let s = \"{\"; // {"), 0);
+        assert_eq!(bracket_delta("let c = '{';"), 0);
+        assert_eq!(bracket_delta("fn f<'a>(x: &'a str) {"), 1);
+    }
+
+    #[test]
+    fn test_levels_and_blanked_lines() {
+        let content = "mod a {\n    fn f() {\n        x();\n    }\n}\nfn g() {}";
+        let mut state = FileState::new(PathBuf::from("test.rs"), content.to_string());
+        state.blank_lines(&[5]);
+
+        let root = Block::from_file(&state);
+        let levels = root.levels();
+
+        assert_eq!(levels.len(), 3);
+        assert_eq!(levels[0][0].len(), 1);
+        assert_eq!(span(&levels[1][0][0]), (1, 4));
+        assert_eq!(span(&levels[2][0][0]), (2, 3));
+    }
+}
@@ -1,8 +1,77 @@
 use crate::command_runner::{CommandRunner, RunResult};
-use crate::file_manager::FileManager;
-use crate::strategy::{ChompRange, Strategy};
-use anyhow::Result;
-use std::collections::HashSet;
+use crate::file_manager::{FileManager, FileState};
+use crate::git::GitHistory;
+use crate::observer::{Aborted, Control, Observer, Outcome};
+use crate::precheck::{Precheck, PrecheckStats};
+use crate::progress::ProgressTracker;
+use crate::strategies::strands_conditionals;
+use crate::strategy::{Chomp, Strategy};
+use anyhow::{Context, Result};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// What the accepted chomps of a run did, for the HTML report
+/// A resumed session continues the journal its earlier legs saved.
+#[derive(Debug, Default, PartialEq)]
+pub struct Journal {
+    /// Candidates tried so far
+    pub attempts: usize,
+    /// Candidates tried and lines remaining after each accepted chomp
+    pub timeline: Vec<(usize, usize)>,
+    /// The strategy that last changed each line, by file and line index
+    pub changed_by: HashMap<PathBuf, BTreeMap<usize, String>>,
+}
+
+impl Journal {
+    /// Save as `attempts: N`, `step: <attempts> <lines>` and `changed: <line>\t<strategy>\t<path>` lines
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let mut content = format!("attempts: {}\n", self.attempts);
+        for (attempts, lines) in &self.timeline {
+            content.push_str(&format!("step: {} {}\n", attempts, lines));
+        }
+        let mut paths: Vec<&PathBuf> = self.changed_by.keys().collect();
+        paths.sort();
+        for path in paths {
+            for (line, strategy) in &self.changed_by[path] {
+                content.push_str(&format!("changed: {}\t{}\t{}\n", line, strategy, path.display()));
+            }
+        }
+        fs::write(path, content).with_context(|| format!("Failed to save the journal to {:?}", path))
+    }
+
+    pub fn load(path: &Path) -> Result<Journal> {
+        let content = fs::read_to_string(path).with_context(|| format!("No journal at {:?}", path))?;
+        let bad = |line: &str| anyhow::anyhow!("Bad journal line {:?}", line);
+        let mut journal = Journal::default();
+        for line in content.lines().filter(|line| !line.is_empty()) {
+            let (key, value) = line.split_once(": ").ok_or_else(|| bad(line))?;
+            match key {
+                "attempts" => journal.attempts = value.parse().map_err(|_| bad(line))?,
+                "step" => {
+                    let (attempts, lines) = value.split_once(' ').ok_or_else(|| bad(line))?;
+                    journal
+                        .timeline
+                        .push((attempts.parse().map_err(|_| bad(line))?, lines.parse().map_err(|_| bad(line))?));
+                }
+                "changed" => {
+                    let mut parts = value.splitn(3, '\t');
+                    let (Some(index), Some(strategy), Some(file)) = (parts.next(), parts.next(), parts.next()) else {
+                        return Err(bad(line));
+                    };
+                    journal
+                        .changed_by
+                        .entry(PathBuf::from(file))
+                        .or_default()
+                        .insert(index.parse().map_err(|_| bad(line))?, strategy.to_string());
+                }
+                _ => return Err(bad(line)),
+            }
+        }
+        Ok(journal)
+    }
+}
 
 /// The Chomper executes chomp attempts using any strategy
 pub struct Chomper {
@@ -10,18 +79,26 @@ pub struct Chomper {
     command_runner: CommandRunner,
     baseline_result: Option<RunResult>,
     tested_states: HashSet<String>,
+    prechecks: Vec<Precheck>,
+    precheck_verdicts: HashMap<String, bool>,
+    precheck_stats: PrecheckStats,
+    command_runs: usize,
+    command_time: Duration,
+    git_history: Option<GitHistory>,
+    observer: Option<Box<dyn Observer>>,
+    /// Progress through the current strategy
+    progress: ProgressTracker,
+    progress_bar: bool,
+    journal: Journal,
+    /// Where the journal is saved after every accepted chomp
+    journal_path: Option<PathBuf>,
     verbose: bool,
 }
 
 impl Chomper {
+    #[cfg(test)]
     pub fn new(file_manager: FileManager, command_runner: CommandRunner) -> Self {
-        Chomper {
-            file_manager,
-            command_runner,
-            baseline_result: None,
-            tested_states: HashSet::new(),
-            verbose: false,
-        }
+        Self::with_verbose(file_manager, command_runner, false)
     }
 
     pub fn with_verbose(file_manager: FileManager, command_runner: CommandRunner, verbose: bool) -> Self {
@@ -30,82 +107,320 @@ impl Chomper {
             command_runner,
             baseline_result: None,
             tested_states: HashSet::new(),
+            prechecks: Vec::new(),
+            precheck_verdicts: HashMap::new(),
+            precheck_stats: PrecheckStats::default(),
+            command_runs: 0,
+            command_time: Duration::ZERO,
+            git_history: None,
+            observer: None,
+            progress: ProgressTracker::new(0),
+            progress_bar: false,
+            journal: Journal::default(),
+            journal_path: None,
             verbose,
         }
     }
 
-    pub fn establish_baseline(&mut self) -> Result<RunResult> {
+    /// Checks every candidate must pass, in order, before the command runs on it
+    pub fn set_prechecks(&mut self, prechecks: Vec<Precheck>) {
+        self.prechecks = prechecks;
+    }
+
+    /// Commit every accepted chomp to this history
+    pub fn set_git_history(&mut self, git_history: GitHistory) {
+        self.git_history = Some(git_history);
+    }
+
+    /// Show each strategy's progress as a bar on stderr
+    pub fn set_progress_bar(&mut self, progress_bar: bool) {
+        self.progress_bar = progress_bar;
+    }
+
+    /// Continue `journal`, e.g. from an earlier leg of a resumed session, and save it to `path`
+    /// after every accepted chomp, so an interrupted run keeps what it learned
+    pub fn set_journal(&mut self, journal: Journal, path: PathBuf) {
+        self.journal = journal;
+        self.journal_path = Some(path);
+    }
+
+    /// Report progress to this observer and let it steer between candidates
+    #[cfg_attr(not(feature = "tui"), allow(dead_code))]
+    pub fn set_observer(&mut self, observer: Box<dyn Observer>) {
+        self.observer = Some(observer);
+    }
+
+    /// Detach the observer, e.g. to tear down a terminal UI before printing the results
+    pub fn clear_observer(&mut self) {
+        self.observer = None;
+    }
+
+    /// Print a warning, or hand it to the observer, whose screen printing would garble
+    fn warn(&mut self, message: &str) {
+        match &mut self.observer {
+            Some(observer) => observer.warning(message),
+            None => self.progress.println(message),
+        }
+    }
+
+    pub fn notify_round(&mut self, round: usize) {
+        if let Some(observer) = &mut self.observer {
+            observer.round_started(round);
+        }
+    }
+
+    fn run_command(&mut self) -> Result<RunResult> {
+        let start = Instant::now();
         let result = self.command_runner.run()?;
+        self.command_runs += 1;
+        self.command_time += start.elapsed();
+        self.progress.record_command(start.elapsed());
+        Ok(result)
+    }
+
+    /// Run the prechecks against the chomped files, caching the verdict per state
+    /// The chomp must already be written to disk for command prechecks
+    fn passes_prechecks(&mut self, key: &str, snapshot: &[FileState]) -> Result<bool> {
+        if let Some(&verdict) = self.precheck_verdicts.get(key) {
+            self.precheck_stats.cache_hits += 1;
+            return Ok(verdict);
+        }
+
+        let start = Instant::now();
+        let after: Vec<&FileState> = snapshot
+            .iter()
+            .filter_map(|saved| self.file_manager.get_file(&saved.path))
+            .collect();
+        let mut verdict = true;
+        for precheck in &self.prechecks {
+            if !precheck.passes(snapshot, &after, self.command_runner.working_dir())? {
+                verdict = false;
+                break;
+            }
+        }
+
+        self.precheck_stats.checked += 1;
+        self.precheck_stats.time += start.elapsed();
+        if !verdict {
+            self.precheck_stats.rejected += 1;
+        }
+        self.precheck_verdicts.insert(key.to_string(), verdict);
+        Ok(verdict)
+    }
+
+    pub fn establish_baseline(&mut self) -> Result<RunResult> {
+        let result = self.run_command()?;
         self.baseline_result = Some(result.clone());
         Ok(result)
     }
 
+    #[allow(dead_code)]
     pub fn baseline_result(&self) -> Option<&RunResult> {
         self.baseline_result.as_ref()
     }
 
-    fn get_state_key(&self) -> String {
-        let mut keys: Vec<_> = self
-            .file_manager
-            .files()
-            .iter()
-            .map(|(path, state)| {
-                let blanked: Vec<_> = state.blanked_lines.iter().copied().collect();
-                format!("{:?}:{:?}", path, blanked)
-            })
-            .collect();
-        keys.sort();
-        keys.join("|")
+    /// Paths of the files a chomp touches
+    fn affected_files(chomp: &Chomp) -> Vec<PathBuf> {
+        match chomp {
+            Chomp::Range(range) => vec![range.file.clone()],
+            Chomp::DeleteFiles(paths) => paths.clone(),
+            Chomp::Edit(edit) => vec![edit.file.clone()],
+            Chomp::Replace(span) => vec![span.file.clone()],
+            Chomp::Compound(parts) => {
+                let mut paths: Vec<PathBuf> = Vec::new();
+                for path in parts.iter().flat_map(Self::affected_files) {
+                    if !paths.contains(&path) {
+                        paths.push(path);
+                    }
+                }
+                paths
+            }
+        }
     }
 
-    fn is_state_tested(&self) -> bool {
-        let key = self.get_state_key();
-        self.tested_states.contains(&key)
+    /// Whether an edit no longer matches the text it was generated for
+    fn is_stale(&self, chomp: &Chomp) -> bool {
+        match chomp {
+            Chomp::Edit(edit) => self
+                .file_manager
+                .get_file(&edit.file)
+                .is_some_and(|state| state.line(edit.line) != edit.expected),
+            Chomp::Replace(span) => self
+                .file_manager
+                .get_file(&span.file)
+                .is_some_and(|state| state.span_text(span.start_line, span.end_line) != span.expected),
+            Chomp::Compound(parts) => parts.iter().any(|part| self.is_stale(part)),
+            Chomp::Range(_) | Chomp::DeleteFiles(_) => false,
+        }
     }
 
-    fn mark_state_tested(&mut self) {
-        let key = self.get_state_key();
-        self.tested_states.insert(key);
+    fn apply_chomp(&mut self, chomp: &Chomp) -> Result<()> {
+        match chomp {
+            Chomp::Range(range) => {
+                let lines_to_blank: Vec<usize> = (range.start_line..range.end_line).collect();
+                match self.file_manager.get_file_mut(&range.file) {
+                    Some(file_state) => file_state.blank_lines(&lines_to_blank),
+                    None => anyhow::bail!("File not found: {:?}", range.file),
+                }
+            }
+            Chomp::DeleteFiles(paths) => {
+                for path in paths {
+                    match self.file_manager.get_file_mut(path) {
+                        Some(file_state) => file_state.delete(),
+                        None => anyhow::bail!("File not found: {:?}", path),
+                    }
+                }
+            }
+            Chomp::Edit(edit) => match self.file_manager.get_file_mut(&edit.file) {
+                // A stale edit changes nothing and gets skipped
+                Some(file_state) if file_state.line(edit.line) == edit.expected => {
+                    file_state.replace_line(edit.line, edit.replacement.clone())
+                }
+                Some(_) => {}
+                None => anyhow::bail!("File not found: {:?}", edit.file),
+            },
+            Chomp::Replace(span) => match self.file_manager.get_file_mut(&span.file) {
+                Some(file_state) if file_state.span_text(span.start_line, span.end_line) == span.expected => {
+                    file_state.replace_span(span.start_line, span.end_line, span.text.clone())
+                }
+                Some(_) => {}
+                None => anyhow::bail!("File not found: {:?}", span.file),
+            },
+            // All or nothing: a partly applied compound would be a different candidate
+            Chomp::Compound(parts) if !self.is_stale(chomp) => {
+                for part in parts {
+                    self.apply_chomp(part)?;
+                }
+            }
+            Chomp::Compound(_) => {}
+        }
+        Ok(())
     }
 
-    /// Try to blank a range of lines and see if tests still pass
-    /// Returns true if the range was successfully blanked
-    pub fn try_blank_range(&mut self, range: &ChompRange) -> Result<bool> {
+    fn restore_snapshot(&mut self, snapshot: Vec<FileState>) {
+        for saved in snapshot {
+            if let Some(file_state) = self.file_manager.get_file_mut(&saved.path) {
+                *file_state = saved;
+            }
+        }
+    }
+
+    /// Undo a chomp that couldn't be tested, in memory and on disk, so it never becomes the
+    /// base of later candidates
+    /// Returns `error`, or the rollback's own error if writing the files back failed too.
+    fn roll_back(&mut self, snapshot: Vec<FileState>, error: anyhow::Error) -> anyhow::Error {
+        self.restore_snapshot(snapshot);
+        match self.file_manager.write_all() {
+            Ok(()) => error,
+            Err(e) => e.context(format!("Failed to roll back an untested chomp after: {:#}", error)),
+        }
+    }
+
+    /// Try to apply a chomp and see if tests still pass
+    /// The strategy that produced the chomp, if given, can veto it before the command runs.
+    /// Returns true if the chomp was kept
+    pub fn try_chomp(&mut self, chomp: &Chomp, strategy: Option<&dyn Strategy>) -> Result<bool> {
+        if let Some(observer) = &mut self.observer {
+            observer.attempt_started(chomp, &self.file_manager);
+        }
+        self.journal.attempts += 1;
+        let outcome = self.attempt(chomp, strategy)?;
+        self.progress.record_chomp(outcome == Outcome::Kept);
+        if let Some(observer) = &mut self.observer {
+            observer.attempt_finished(chomp, outcome, &self.file_manager);
+        }
+        Ok(outcome == Outcome::Kept)
+    }
+
+    fn attempt(&mut self, chomp: &Chomp, strategy: Option<&dyn Strategy>) -> Result<Outcome> {
+        if self.verbose {
+            println!("    🎯 Testing chomp: {}", chomp);
+        }
+
+        // Snapshot the files this chomp touches so it can be rolled back as a whole
+        let affected = Self::affected_files(chomp);
+        let snapshot: Vec<FileState> = affected
+            .iter()
+            .filter_map(|path| self.file_manager.get_file(path).cloned())
+            .collect();
+        let key_before = self.file_manager.state_key();
+
+        if let Err(e) = self.apply_chomp(chomp) {
+            self.restore_snapshot(snapshot);
+            return Err(e);
+        }
+
+        let key = self.file_manager.state_key();
+
+        // Nothing left to chomp here, e.g. the lines were already blanked
+        if key == key_before {
+            if self.verbose {
+                println!("    ⏭️  Skipping chomp that changes nothing");
+            }
+            self.restore_snapshot(snapshot);
+            return Ok(Outcome::Skipped);
+        }
+
         // Check if we've already tested this state
-        if self.is_state_tested() {
+        if self.tested_states.contains(&key) {
             if self.verbose {
                 println!("    ⏭️  Skipping already-tested state");
             }
-            return Ok(false);
+            self.restore_snapshot(snapshot);
+            return Ok(Outcome::Skipped);
         }
 
-        // Blank the lines in the range
-        let lines_to_blank: Vec<usize> = (range.start_line..range.end_line).collect();
-
-        if self.verbose {
-            println!(
-                "    🎯 Testing range: {:?} lines {}-{} ({} lines)",
-                range.file,
-                range.start_line,
-                range.end_line,
-                range.end_line - range.start_line
-            );
+        // Let the strategy reject candidates that aren't worth a run
+        if let Some(strategy) = strategy {
+            if !strategy.accepts(self.file_manager.files(), &affected) {
+                if self.verbose {
+                    println!("    ⏭️  Skipping chomp rejected by {}", strategy.name());
+                }
+                self.restore_snapshot(snapshot);
+                return Ok(Outcome::Skipped);
+            }
         }
 
-        if let Some(file_state) = self.file_manager.get_file_mut(&range.file) {
-            file_state.blank_lines(&lines_to_blank);
-        } else {
-            anyhow::bail!("File not found: {:?}", range.file);
+        // Whatever the strategy, a stranded `#if` or `#endif` can't preprocess
+        if strands_conditionals(&snapshot, self.file_manager.files()) {
+            if self.verbose {
+                println!("    ⏭️  Skipping chomp that leaves an unmatched #if or #endif");
+            }
+            self.restore_snapshot(snapshot);
+            return Ok(Outcome::Skipped);
         }
 
         // Write the changes
-        self.file_manager.write_all()?;
+        if let Err(e) = self.file_manager.write_all() {
+            return Err(self.roll_back(snapshot, e));
+        }
+
+        // Cheap checks first, so candidates that can't work never run the full command
+        let passes = match self.prechecks.is_empty() {
+            true => Ok(true),
+            false => self.passes_prechecks(&key, &snapshot),
+        };
+        let passes = match passes {
+            Ok(passes) => passes,
+            Err(e) => return Err(self.roll_back(snapshot, e)),
+        };
+        if !passes {
+            if self.verbose {
+                println!("    ⏭️  Skipping chomp rejected by precheck");
+            }
+            self.restore_snapshot(snapshot);
+            self.file_manager.write_all()?;
+            return Ok(Outcome::Skipped);
+        }
 
         // Run the command
-        let result = self.command_runner.run()?;
+        let result = match self.run_command() {
+            Ok(result) => result,
+            Err(e) => return Err(self.roll_back(snapshot, e)),
+        };
 
         // Mark this state as tested
-        self.mark_state_tested();
+        self.tested_states.insert(key);
 
         // Check if result matches baseline
         let matches = if let Some(baseline) = &self.baseline_result {
@@ -116,43 +431,118 @@ impl Chomper {
 
         if self.verbose {
             if matches {
-                println!("    ✅ SUCCESS: Range can be removed!");
+                println!("    ✅ SUCCESS: Chomp can be kept!");
             } else {
-                println!("    ❌ FAILED: Output differs from baseline, restoring lines");
+                println!("    ❌ FAILED: Output differs from baseline, restoring");
             }
         }
 
-        // If it doesn't match, restore the lines
+        // If it doesn't match, roll the chomp back
         if !matches {
-            if let Some(file_state) = self.file_manager.get_file_mut(&range.file) {
-                file_state.unblank_lines(&lines_to_blank);
-            }
+            self.restore_snapshot(snapshot);
             self.file_manager.write_all()?;
+            return Ok(Outcome::Rejected);
         }
 
-        Ok(matches)
+        self.record_in_journal(&snapshot, strategy.map_or("manual", |s| s.name()));
+        if let Some(path) = &self.journal_path {
+            // Best effort: the journal only feeds the reports, and the chomp is kept either way
+            let _ = self.journal.save(path);
+        }
+        if let Some(git_history) = &mut self.git_history {
+            let strategy = strategy.map_or("manual", |s| s.name());
+            let message = format!(
+                "chompie: {} chomp\n\nStrategy: {}\nSpan: {}\nLines remaining: {}",
+                strategy,
+                strategy,
+                chomp,
+                self.file_manager.non_blank_lines()
+            );
+            // The chomp is kept either way; a missing commit only leaves a gap in the history
+            if let Err(e) = git_history.record(&message, &affected) {
+                self.warn(&format!("⚠️  Failed to commit chomp: {:#}", e));
+            }
+        }
+
+        Ok(Outcome::Kept)
+    }
+
+    /// Attribute the lines an accepted chomp changed, compared to `before`, to `strategy`
+    fn record_in_journal(&mut self, before: &[FileState], strategy: &str) {
+        for saved in before {
+            let Some(after) = self.file_manager.get_file(&saved.path) else {
+                continue;
+            };
+            let changed_by = self.journal.changed_by.entry(saved.path.clone()).or_default();
+            for line in 0..saved.total_lines() {
+                let deleted_now = after.deleted && !saved.deleted && !saved.line(line).is_empty();
+                if deleted_now || saved.line(line) != after.line(line) {
+                    changed_by.insert(line, strategy.to_string());
+                }
+            }
+        }
+        self.journal.timeline.push((self.journal.attempts, self.file_manager.non_blank_lines()));
     }
 
     /// Execute a strategy and return number of successful chomps
     pub fn execute_strategy(&mut self, strategy: &dyn Strategy) -> Result<usize> {
-        let ranges = strategy.generate_ranges(self.file_manager.files());
+        let mut chomps = strategy.generate_chomps(self.file_manager.files());
         let mut successful = 0;
+        // Only kept for strategies that regenerate, so regenerated candidates aren't retried
+        let mut tried: HashSet<Chomp> = HashSet::new();
 
         if self.verbose {
-            println!("  📊 Strategy generated {} ranges to test", ranges.len());
+            println!("  📊 Strategy generated {} chomps to test", chomps.len());
+        }
+        if let Some(observer) = &mut self.observer {
+            observer.strategy_started(strategy.name(), chomps.len(), &self.file_manager);
         }
+        self.progress = match self.progress_bar {
+            true => ProgressTracker::with_bar(chomps.len(), strategy.name()),
+            false => ProgressTracker::new(chomps.len()),
+        };
 
-        for (idx, range) in ranges.iter().enumerate() {
+        let mut idx = 0;
+        while idx < chomps.len() {
+            let chomp = &chomps[idx];
+            let control = self.observer.as_mut().map_or(Control::Continue, |observer| observer.control());
+            match control {
+                Control::Continue => {}
+                Control::SkipStrategy => break,
+                Control::Abort => {
+                    self.progress.finish();
+                    return Err(Aborted.into());
+                }
+            }
             if self.verbose {
-                println!("  🔍 Attempt {}/{}", idx + 1, ranges.len());
+                println!("  🔍 Attempt {}/{}", self.progress.chomps_performed() + 1, self.progress.total_ranges());
             }
 
-            match self.try_blank_range(range) {
+            let kept = self.try_chomp(chomp, Some(strategy));
+            if strategy.regenerates() {
+                tried.insert(chomp.clone());
+            }
+            idx += 1;
+
+            match kept {
                 Ok(true) => {
                     successful += 1;
                     if self.verbose {
                         println!("  💚 Total successful so far: {}", successful);
                     }
+                    if strategy.regenerates() {
+                        chomps = strategy
+                            .generate_chomps(self.file_manager.files())
+                            .into_iter()
+                            .filter(|chomp| !tried.contains(chomp))
+                            .collect();
+                        idx = 0;
+                        let performed = self.progress.chomps_performed();
+                        self.progress.set_total_ranges(performed + chomps.len());
+                        if self.verbose {
+                            println!("  📊 Strategy regenerated {} chomps to test", chomps.len());
+                        }
+                    }
                 }
                 Ok(false) => {
                     if self.verbose {
@@ -160,7 +550,7 @@ impl Chomper {
                     }
                 },
                 Err(e) => {
-                    eprintln!("Error during chomp: {}", e);
+                    self.warn(&format!("Error during chomp: {}", e));
                     if self.verbose {
                         eprintln!("  ❗ Error details: {:#}", e);
                     }
@@ -168,6 +558,10 @@ impl Chomper {
             }
         }
 
+        self.progress.finish();
+        if self.verbose {
+            self.progress.display();
+        }
         Ok(successful)
     }
 
@@ -175,16 +569,51 @@ impl Chomper {
         &self.file_manager
     }
 
+    /// The chomped files, once chomping is done
+    pub fn into_file_manager(self) -> FileManager {
+        self.file_manager
+    }
+
     pub fn chomps_tested(&self) -> usize {
         self.tested_states.len()
     }
+
+    /// Runs of the full command so far, baseline included
+    pub fn command_runs(&self) -> usize {
+        self.command_runs
+    }
+
+    pub fn git_history(&self) -> Option<&GitHistory> {
+        self.git_history.as_ref()
+    }
+
+    pub fn journal(&self) -> &Journal {
+        &self.journal
+    }
+
+    /// Progress through the strategy running now, or the last one
+    pub fn progress(&self) -> &ProgressTracker {
+        &self.progress
+    }
+
+    pub fn precheck_stats(&self) -> &PrecheckStats {
+        &self.precheck_stats
+    }
+
+    /// Average duration of one run of the full command, baseline included
+    pub fn average_command_time(&self) -> Duration {
+        if self.command_runs == 0 {
+            return Duration::ZERO;
+        }
+        self.command_time / self.command_runs as u32
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::strategies::BisectionStrategy;
-    use std::path::PathBuf;
+    use crate::strategy::ChompRange;
     use tempfile::TempDir;
 
     #[test]
@@ -216,6 +645,7 @@ mod tests {
 
         // Command that doesn't depend on file content
         let runner = CommandRunner::new("echo constant".to_string());
+        let initial = manager.files().clone();
         let mut chomper = Chomper::new(manager, runner);
 
         chomper.establish_baseline().unwrap();
@@ -225,5 +655,331 @@ mod tests {
 
         // Should successfully chomp since command output is constant
         assert!(successful > 0);
+
+        // Every candidate is counted, and only the ones that ran the command are timed
+        let progress = chomper.progress();
+        assert_eq!(progress.total_ranges(), BisectionStrategy.generate_chomps(&initial).len());
+        assert_eq!(progress.chomps_performed(), progress.total_ranges());
+        assert_eq!(progress.successful_chomps(), successful);
+        assert!(progress.average_command_time() > Duration::ZERO);
+    }
+
+    /// Proposes the last line left and the first line, generated again after each kept chomp
+    struct LastLineStrategy;
+
+    impl Strategy for LastLineStrategy {
+        fn name(&self) -> &str {
+            "last_line"
+        }
+
+        fn generate_ranges(&self, files: &HashMap<PathBuf, FileState>) -> Vec<ChompRange> {
+            let mut ranges = Vec::new();
+            for (path, state) in files {
+                if let Some(last) = state.non_blank_line_indices().last() {
+                    ranges.push(ChompRange { file: path.clone(), start_line: *last, end_line: last + 1 });
+                }
+                ranges.push(ChompRange { file: path.clone(), start_line: 0, end_line: 1 });
+            }
+            ranges
+        }
+
+        fn regenerates(&self) -> bool {
+            true
+        }
+    }
+
+    #[test]
+    fn test_execute_strategy_regenerates() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("test.txt");
+        std::fs::write(&file_path, "line1\nline2\nline3").unwrap();
+
+        let mut manager = crate::file_manager::FileManager::new();
+        manager.add_file(&file_path).unwrap();
+        let mut chomper = Chomper::new(manager, CommandRunner::new("echo constant".to_string()));
+        chomper.establish_baseline().unwrap();
+
+        // One candidate at a time reaches every line, and the first line is only tried once
+        assert_eq!(chomper.execute_strategy(&LastLineStrategy).unwrap(), 3);
+        assert_eq!(chomper.file_manager().non_blank_lines(), 0);
+        let progress = chomper.progress();
+        assert_eq!(progress.chomps_performed(), progress.total_ranges());
+    }
+
+    #[test]
+    fn test_try_chomp_rolls_back_failure() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("test.txt");
+        std::fs::write(&file_path, "keep\nline2\nline3").unwrap();
+
+        let mut manager = crate::file_manager::FileManager::new();
+        manager.add_file(&file_path).unwrap();
+
+        // Output depends on the first line of the file
+        let command = format!("head -n 1 {}", file_path.display());
+        let mut chomper = Chomper::new(manager, CommandRunner::new(command));
+        chomper.establish_baseline().unwrap();
+
+        let keep = Chomp::Range(ChompRange { file: file_path.clone(), start_line: 0, end_line: 1 });
+        assert!(!chomper.try_chomp(&keep, None).unwrap());
+        assert_eq!(std::fs::read_to_string(&file_path).unwrap(), "keep\nline2\nline3");
+
+        // Later chomps are still tried after an earlier success
+        let first = Chomp::Range(ChompRange { file: file_path.clone(), start_line: 1, end_line: 2 });
+        let second = Chomp::Range(ChompRange { file: file_path.clone(), start_line: 2, end_line: 3 });
+        assert!(chomper.try_chomp(&first, None).unwrap());
+        assert!(chomper.try_chomp(&second, None).unwrap());
+
+        // Chomping already-blank lines is skipped without running the command
+        let tested = chomper.chomps_tested();
+        assert!(!chomper.try_chomp(&second, None).unwrap());
+        assert_eq!(chomper.chomps_tested(), tested);
+    }
+
+    #[test]
+    fn test_try_chomp_delete_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("unused.txt");
+        std::fs::write(&file_path, "unused").unwrap();
+
+        let mut manager = crate::file_manager::FileManager::new();
+        manager.add_file(&file_path).unwrap();
+
+        let mut chomper = Chomper::new(manager, CommandRunner::new("echo constant".to_string()));
+        chomper.establish_baseline().unwrap();
+
+        assert!(chomper.try_chomp(&Chomp::DeleteFiles(vec![file_path.clone()]), None).unwrap());
+        assert!(!file_path.exists());
+        assert_eq!(chomper.file_manager().non_blank_lines(), 0);
+    }
+
+    #[test]
+    fn test_try_chomp_compound_is_all_or_nothing() {
+        let temp_dir = TempDir::new().unwrap();
+        let parent = temp_dir.path().join("main.rs");
+        let child = temp_dir.path().join("a.rs");
+        std::fs::write(&parent, "mod a;").unwrap();
+        std::fs::write(&child, "fn f() {}").unwrap();
+
+        let mut manager = crate::file_manager::FileManager::new();
+        manager.add_file(&parent).unwrap();
+        manager.add_file(&child).unwrap();
+
+        let mut chomper = Chomper::new(manager, CommandRunner::new("echo constant".to_string()));
+        chomper.establish_baseline().unwrap();
+
+        let inline = |expected: &str| {
+            Chomp::Compound(vec![
+                Chomp::Edit(crate::strategy::LineEdit {
+                    file: parent.clone(),
+                    line: 0,
+                    expected: expected.to_string(),
+                    replacement: "mod a {\n    fn f() {}\n}".to_string(),
+                }),
+                Chomp::DeleteFiles(vec![child.clone()]),
+            ])
+        };
+
+        // A stale part skips the whole compound, so the file isn't deleted on its own
+        assert!(!chomper.try_chomp(&inline("mod b;"), None).unwrap());
+        assert!(child.exists());
+
+        assert!(chomper.try_chomp(&inline("mod a;"), None).unwrap());
+        assert!(!child.exists());
+        assert_eq!(std::fs::read_to_string(&parent).unwrap(), "mod a {\n    fn f() {}\n}");
+    }
+
+    #[test]
+    fn test_journal() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("test.txt");
+        let other = temp_dir.path().join("other.txt");
+        std::fs::write(&file_path, "keep\nline2\nline3").unwrap();
+        std::fs::write(&other, "x").unwrap();
+
+        let mut manager = crate::file_manager::FileManager::new();
+        manager.add_file(&file_path).unwrap();
+        manager.add_file(&other).unwrap();
+        let command = format!("head -n 1 {}", file_path.display());
+        let mut chomper = Chomper::new(manager, CommandRunner::new(command));
+        chomper.establish_baseline().unwrap();
+
+        let keep = Chomp::Range(ChompRange { file: file_path.clone(), start_line: 0, end_line: 1 });
+        let rest = Chomp::Range(ChompRange { file: file_path.clone(), start_line: 1, end_line: 3 });
+        chomper.try_chomp(&keep, None).unwrap();
+        chomper.try_chomp(&rest, Some(&BisectionStrategy)).unwrap();
+        chomper.try_chomp(&Chomp::DeleteFiles(vec![other.clone()]), None).unwrap();
+
+        let journal = chomper.journal();
+        assert_eq!(journal.attempts, 3);
+        assert_eq!(journal.timeline, vec![(2, 2), (3, 1)]);
+        let bisection = "bisection".to_string();
+        assert_eq!(journal.changed_by[&file_path], BTreeMap::from([(1, bisection.clone()), (2, bisection)]));
+        assert_eq!(journal.changed_by[&other], BTreeMap::from([(0, "manual".to_string())]));
+
+        let path = temp_dir.path().join("journal");
+        journal.save(&path).unwrap();
+        assert_eq!(&Journal::load(&path).unwrap(), journal);
+    }
+
+    #[test]
+    fn test_journal_continues() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("test.txt");
+        std::fs::write(&file_path, "line1\nline2").unwrap();
+        let path = temp_dir.path().join("journal");
+
+        let earlier = Journal {
+            attempts: 5,
+            timeline: vec![(5, 3)],
+            changed_by: HashMap::from([(file_path.clone(), BTreeMap::from([(2, "hdd".to_string())]))]),
+        };
+        let mut manager = crate::file_manager::FileManager::new();
+        manager.add_file(&file_path).unwrap();
+        let mut chomper = Chomper::new(manager, CommandRunner::new("echo constant".to_string()));
+        chomper.set_journal(earlier, path.clone());
+        chomper.establish_baseline().unwrap();
+
+        let chomp = Chomp::Range(ChompRange { file: file_path.clone(), start_line: 0, end_line: 1 });
+        assert!(chomper.try_chomp(&chomp, None).unwrap());
+        let saved = Journal::load(&path).unwrap();
+        assert_eq!(saved.timeline, vec![(5, 3), (6, 1)]);
+        assert_eq!(saved.changed_by[&file_path], BTreeMap::from([(0, "manual".to_string()), (2, "hdd".to_string())]));
+        assert!(Journal::load(&temp_dir.path().join("missing")).is_err());
+    }
+
+    /// Records outcomes and steers by a script of controls
+    struct Recorder {
+        outcomes: std::rc::Rc<std::cell::RefCell<Vec<Outcome>>>,
+        controls: Vec<Control>,
+    }
+
+    impl Observer for Recorder {
+        fn attempt_finished(&mut self, _chomp: &Chomp, outcome: Outcome, _files: &FileManager) {
+            self.outcomes.borrow_mut().push(outcome);
+        }
+
+        fn control(&mut self) -> Control {
+            self.controls.pop().unwrap_or(Control::Continue)
+        }
+    }
+
+    #[test]
+    fn test_observer() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("test.txt");
+        std::fs::write(&file_path, "keep\nline2\nline3\nline4").unwrap();
+
+        let mut manager = crate::file_manager::FileManager::new();
+        manager.add_file(&file_path).unwrap();
+        let command = format!("head -n 1 {}", file_path.display());
+        let mut chomper = Chomper::new(manager, CommandRunner::new(command));
+        chomper.establish_baseline().unwrap();
+
+        let outcomes = std::rc::Rc::new(std::cell::RefCell::new(Vec::new()));
+        chomper.set_observer(Box::new(Recorder { outcomes: outcomes.clone(), controls: Vec::new() }));
+        let keep = Chomp::Range(ChompRange { file: file_path.clone(), start_line: 0, end_line: 1 });
+        let line2 = Chomp::Range(ChompRange { file: file_path.clone(), start_line: 1, end_line: 2 });
+        chomper.try_chomp(&keep, None).unwrap();
+        chomper.try_chomp(&line2, None).unwrap();
+        chomper.try_chomp(&line2, None).unwrap();
+        assert_eq!(*outcomes.borrow(), vec![Outcome::Rejected, Outcome::Kept, Outcome::Skipped]);
+
+        // Skipping ends the strategy early, and aborting fails the run
+        let controls = vec![Control::SkipStrategy, Control::Continue];
+        chomper.set_observer(Box::new(Recorder { outcomes: outcomes.clone(), controls }));
+        outcomes.borrow_mut().clear();
+        chomper.execute_strategy(&BisectionStrategy).unwrap();
+        assert_eq!(outcomes.borrow().len(), 1);
+
+        chomper.set_observer(Box::new(Recorder { outcomes, controls: vec![Control::Abort] }));
+        let error = chomper.execute_strategy(&BisectionStrategy).unwrap_err();
+        assert!(error.is::<Aborted>());
+    }
+
+    #[test]
+    fn test_precheck_rejects_before_command() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("test.txt");
+        std::fs::write(&file_path, "keep\nline2").unwrap();
+
+        let mut manager = crate::file_manager::FileManager::new();
+        manager.add_file(&file_path).unwrap();
+
+        let mut chomper = Chomper::new(manager, CommandRunner::new("echo constant".to_string()));
+        let precheck = format!("grep -q keep {}", file_path.display());
+        chomper.set_prechecks(vec![Precheck::Command(precheck)]);
+        chomper.establish_baseline().unwrap();
+
+        let keep = Chomp::Range(ChompRange { file: file_path.clone(), start_line: 0, end_line: 1 });
+        assert!(!chomper.try_chomp(&keep, None).unwrap());
+        assert_eq!(chomper.chomps_tested(), 0);
+        assert_eq!(chomper.precheck_stats().rejected, 1);
+
+        let other = Chomp::Range(ChompRange { file: file_path.clone(), start_line: 1, end_line: 2 });
+        assert!(chomper.try_chomp(&other, None).unwrap());
+        assert_eq!(chomper.precheck_stats().checked, 2);
+    }
+
+    #[test]
+    fn test_precheck_error_rolls_back() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("test.txt");
+        std::fs::write(&file_path, "line1\nline2").unwrap();
+        let working_dir = temp_dir.path().join("work");
+        std::fs::create_dir(&working_dir).unwrap();
+
+        let mut manager = crate::file_manager::FileManager::new();
+        manager.add_file(&file_path).unwrap();
+        let runner = CommandRunner::new("echo constant".to_string()).with_working_dir(Some(working_dir.clone()));
+        let mut chomper = Chomper::new(manager, runner);
+        chomper.set_prechecks(vec![Precheck::Command("true".to_string())]);
+        chomper.establish_baseline().unwrap();
+
+        // Neither the precheck nor the command can start without their working directory
+        std::fs::remove_dir(&working_dir).unwrap();
+        let chomp = Chomp::Range(ChompRange { file: file_path.clone(), start_line: 0, end_line: 1 });
+        assert!(chomper.try_chomp(&chomp, None).is_err());
+        assert_eq!(std::fs::read_to_string(&file_path).unwrap(), "line1\nline2");
+        assert_eq!(chomper.file_manager().non_blank_lines(), 2);
+
+        chomper.set_prechecks(Vec::new());
+        assert!(chomper.try_chomp(&chomp, None).is_err());
+        assert_eq!(std::fs::read_to_string(&file_path).unwrap(), "line1\nline2");
+        assert_eq!(chomper.chomps_tested(), 0);
+
+        // With an observer attached, the errors go to it rather than the terminal
+        let warnings = std::rc::Rc::new(std::cell::RefCell::new(Vec::new()));
+        chomper.set_observer(Box::new(Warnings(warnings.clone())));
+        assert_eq!(chomper.execute_strategy(&BisectionStrategy).unwrap(), 0);
+        assert!(!warnings.borrow().is_empty());
+        assert!(warnings.borrow().iter().all(|warning| warning.starts_with("Error during chomp: ")));
+    }
+
+    #[test]
+    fn test_unmatched_conditionals_skipped() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("main.c");
+        std::fs::write(&file_path, "#ifdef DEBUG\nlog();\n#endif\nint main() {}").unwrap();
+
+        let mut manager = crate::file_manager::FileManager::new();
+        manager.add_file(&file_path).unwrap();
+        let mut chomper = Chomper::new(manager, CommandRunner::new("echo constant".to_string()));
+        chomper.establish_baseline().unwrap();
+
+        // Line-based strategies may cut a region in half, but never get to run it
+        let endif = Chomp::Range(ChompRange { file: file_path.clone(), start_line: 2, end_line: 4 });
+        assert!(!chomper.try_chomp(&endif, Some(&BisectionStrategy)).unwrap());
+        assert_eq!(chomper.chomps_tested(), 0);
+        let region = Chomp::Range(ChompRange { file: file_path.clone(), start_line: 0, end_line: 3 });
+        assert!(chomper.try_chomp(&region, Some(&BisectionStrategy)).unwrap());
+    }
+
+    struct Warnings(std::rc::Rc<std::cell::RefCell<Vec<String>>>);
+
+    impl Observer for Warnings {
+        fn warning(&mut self, message: &str) {
+            self.0.borrow_mut().push(message.to_string());
+        }
     }
 }
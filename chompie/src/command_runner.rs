@@ -1,4 +1,5 @@
 use anyhow::Result;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -16,16 +17,27 @@ impl RunResult {
 
 pub struct CommandRunner {
     command: String,
+    /// Directory the command runs in, the current directory if None
+    working_dir: Option<PathBuf>,
     verbose: bool,
 }
 
 impl CommandRunner {
     pub fn new(command: String) -> Self {
-        CommandRunner { command, verbose: false }
+        CommandRunner { command, working_dir: None, verbose: false }
     }
 
     pub fn with_verbose(command: String, verbose: bool) -> Self {
-        CommandRunner { command, verbose }
+        CommandRunner { command, working_dir: None, verbose }
+    }
+
+    pub fn with_working_dir(mut self, working_dir: Option<PathBuf>) -> Self {
+        self.working_dir = working_dir;
+        self
+    }
+
+    pub fn working_dir(&self) -> Option<&Path> {
+        self.working_dir.as_deref()
     }
 
     pub fn run(&self) -> Result<RunResult> {
@@ -33,16 +45,19 @@ impl CommandRunner {
             println!("      🔧 Running command: {}", self.command);
         }
 
-        let output = if cfg!(target_os = "windows") {
-            Command::new("cmd")
-                .args(["/C", &self.command])
-                .output()?
+        let mut command = if cfg!(target_os = "windows") {
+            let mut command = Command::new("cmd");
+            command.args(["/C", &self.command]);
+            command
         } else {
-            Command::new("sh")
-                .arg("-c")
-                .arg(&self.command)
-                .output()?
+            let mut command = Command::new("sh");
+            command.arg("-c").arg(&self.command);
+            command
         };
+        if let Some(working_dir) = &self.working_dir {
+            command.current_dir(working_dir);
+        }
+        let output = command.output()?;
 
         let result = RunResult {
             stdout: String::from_utf8_lossy(&output.stdout).to_string(),
@@ -95,6 +110,15 @@ mod tests {
         assert_eq!(result.exit_code, 42);
     }
 
+    #[test]
+    fn test_run_in_working_dir() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        std::fs::write(temp_dir.path().join("marker.txt"), "here").unwrap();
+        let runner = CommandRunner::new("cat marker.txt".to_string())
+            .with_working_dir(Some(temp_dir.path().to_path_buf()));
+        assert_eq!(runner.run().unwrap().stdout, "here");
+    }
+
     #[test]
     fn test_result_identical() {
         let result1 = RunResult {
@@ -0,0 +1,86 @@
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
+
+/// Line coverage read from an lcov tracefile
+///
+/// Most coverage tools can write lcov: `cargo llvm-cov --lcov`, `coverage lcov` for
+/// coverage.py, `gcovr --lcov`, and converters for `go test -coverprofile`.
+#[derive(Debug, Default)]
+pub struct Coverage {
+    /// Source path as written in the tracefile -> 0-based line -> hit count
+    files: HashMap<PathBuf, HashMap<usize, u64>>,
+}
+
+impl Coverage {
+    pub fn load(path: &Path) -> Result<Coverage> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read lcov file {:?}", path))?;
+        Ok(Coverage::parse_lcov(&content))
+    }
+
+    pub fn parse_lcov(content: &str) -> Coverage {
+        let mut files: HashMap<PathBuf, HashMap<usize, u64>> = HashMap::new();
+        let mut current: Option<PathBuf> = None;
+
+        for record in content.lines() {
+            let record = record.trim();
+            if let Some(path) = record.strip_prefix("SF:") {
+                current = Some(PathBuf::from(path));
+            } else if record == "end_of_record" {
+                current = None;
+            } else if let (Some(path), Some(data)) = (&current, record.strip_prefix("DA:")) {
+                let mut fields = data.split(',');
+                let line = fields.next().and_then(|f| f.trim().parse::<usize>().ok());
+                let hits = fields.next().and_then(|f| f.trim().parse::<u64>().ok());
+                if let (Some(line), Some(hits)) = (line, hits) {
+                    // The same line can be listed more than once, e.g. for generics
+                    let entry = files.entry(path.clone()).or_default().entry(line.saturating_sub(1)).or_insert(0);
+                    *entry += hits;
+                }
+            }
+        }
+
+        Coverage { files }
+    }
+
+    /// Hit counts for a file being chomped, by 0-based line
+    /// Tracefiles usually hold absolute paths, so paths are matched canonically or by suffix
+    pub fn for_file(&self, path: &Path) -> Option<&HashMap<usize, u64>> {
+        let canonical = path.canonicalize().ok();
+        let relative: PathBuf = path
+            .components()
+            .filter(|component| !matches!(component, Component::CurDir))
+            .collect();
+
+        self.files
+            .iter()
+            .find(|(source, _)| canonical.is_some() && source.canonicalize().ok() == canonical)
+            .or_else(|| self.files.iter().find(|(source, _)| source.ends_with(&relative)))
+            .map(|(_, lines)| lines)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LCOV: &str = "TN:\nSF:/home/me/project/src/main.rs\nDA:1,1\nDA:2,0\nDA:2,3\nDA:5,0\nend_of_record\nSF:/home/me/project/src/util.rs\nDA:1,0\nend_of_record\n";
+
+    #[test]
+    fn test_parse_lcov() {
+        let coverage = Coverage::parse_lcov(LCOV);
+        let main = coverage.for_file(Path::new("./src/main.rs")).unwrap();
+        assert_eq!(main.get(&0), Some(&1));
+        assert_eq!(main.get(&1), Some(&3));
+        assert_eq!(main.get(&4), Some(&0));
+        assert_eq!(main.get(&2), None);
+
+        assert_eq!(coverage.for_file(Path::new("src/util.rs")).unwrap().get(&0), Some(&0));
+        assert!(coverage.for_file(Path::new("src/other.rs")).is_none());
+    }
+}
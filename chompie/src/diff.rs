@@ -0,0 +1,138 @@
+/// Lines of context around each change
+const CONTEXT: usize = 3;
+
+/// Past this many line pairs the middle of a file is shown as replaced wholesale instead of
+/// being diffed line by line
+const MAX_CELLS: usize = 25_000_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Op {
+    Keep(usize, usize),
+    Remove(usize),
+    Add(usize),
+}
+
+/// A unified diff from `old` to `new`, empty if they're equal
+/// `new` is None for a file that was deleted.
+pub fn unified(name: &str, old: &str, new: Option<&str>) -> String {
+    let old_lines: Vec<&str> = old.lines().collect();
+    let new_lines: Vec<&str> = new.map(|new| new.lines().collect()).unwrap_or_default();
+    let ops = edit_script(&old_lines, &new_lines);
+    let changes: Vec<usize> = (0..ops.len()).filter(|&i| !matches!(ops[i], Op::Keep(..))).collect();
+    if changes.is_empty() && new.is_some() {
+        return String::new();
+    }
+
+    let mut out = format!("--- a/{}\n", name);
+    match new {
+        Some(_) => out.push_str(&format!("+++ b/{}\n", name)),
+        None => out.push_str("+++ /dev/null\n"),
+    }
+
+    // Changes closer than twice the context share a hunk
+    let mut hunks: Vec<(usize, usize)> = Vec::new();
+    for &change in &changes {
+        let start = change.saturating_sub(CONTEXT);
+        let end = (change + CONTEXT + 1).min(ops.len());
+        match hunks.last_mut() {
+            Some(last) if start <= last.1 => last.1 = end,
+            _ => hunks.push((start, end)),
+        }
+    }
+
+    for (start, end) in hunks {
+        let old_before = ops[..start].iter().filter(|op| !matches!(op, Op::Add(_))).count();
+        let new_before = ops[..start].iter().filter(|op| !matches!(op, Op::Remove(_))).count();
+        let old_count = ops[start..end].iter().filter(|op| !matches!(op, Op::Add(_))).count();
+        let new_count = ops[start..end].iter().filter(|op| !matches!(op, Op::Remove(_))).count();
+        // An empty side is numbered after the line it follows, like diff -u does
+        let position = |before: usize, count: usize| if count == 0 { before } else { before + 1 };
+        out.push_str(&format!(
+            "@@ -{},{} +{},{} @@\n",
+            position(old_before, old_count),
+            old_count,
+            position(new_before, new_count),
+            new_count
+        ));
+        for op in &ops[start..end] {
+            match *op {
+                Op::Keep(i, _) => out.push_str(&format!(" {}\n", old_lines[i])),
+                Op::Remove(i) => out.push_str(&format!("-{}\n", old_lines[i])),
+                Op::Add(j) => out.push_str(&format!("+{}\n", new_lines[j])),
+            }
+        }
+    }
+    out
+}
+
+/// Shortest edit script by longest common subsequence, after trimming the common prefix and
+/// suffix, which is most of a chomped file since chomping keeps line numbers
+fn edit_script(old: &[&str], new: &[&str]) -> Vec<Op> {
+    let prefix = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..].iter().rev().zip(new[prefix..].iter().rev()).take_while(|(a, b)| a == b).count();
+    let (old_mid, new_mid) = (&old[prefix..old.len() - suffix], &new[prefix..new.len() - suffix]);
+
+    let mut ops: Vec<Op> = (0..prefix).map(|i| Op::Keep(i, i)).collect();
+    let (n, m) = (old_mid.len(), new_mid.len());
+    if n * m > MAX_CELLS {
+        ops.extend((0..n).map(|i| Op::Remove(prefix + i)));
+        ops.extend((0..m).map(|j| Op::Add(prefix + j)));
+    } else {
+        // lcs[i][j]: length of the LCS of old_mid[i..] and new_mid[j..]
+        let mut lcs = vec![vec![0u32; m + 1]; n + 1];
+        for i in (0..n).rev() {
+            for j in (0..m).rev() {
+                lcs[i][j] = if old_mid[i] == new_mid[j] {
+                    lcs[i + 1][j + 1] + 1
+                } else {
+                    lcs[i + 1][j].max(lcs[i][j + 1])
+                };
+            }
+        }
+        let (mut i, mut j) = (0, 0);
+        while i < n || j < m {
+            if i < n && j < m && old_mid[i] == new_mid[j] {
+                ops.push(Op::Keep(prefix + i, prefix + j));
+                i += 1;
+                j += 1;
+            } else if j == m || (i < n && lcs[i + 1][j] >= lcs[i][j + 1]) {
+                ops.push(Op::Remove(prefix + i));
+                i += 1;
+            } else {
+                ops.push(Op::Add(prefix + j));
+                j += 1;
+            }
+        }
+    }
+
+    let (old_suffix, new_suffix) = (old.len() - suffix, new.len() - suffix);
+    ops.extend((0..suffix).map(|k| Op::Keep(old_suffix + k, new_suffix + k)));
+    ops
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unified_blanked_lines() {
+        let old = "a\nb\nc\nd\ne\nf\ng\nh\ni\nj\nk\nl";
+        let new = "a\n\nc\nd\ne\nf\ng\nh\ni\nj\nk\n";
+        assert_eq!(
+            unified("src/x.rs", old, Some(new)),
+            "--- a/src/x.rs\n+++ b/src/x.rs\n\
+             @@ -1,5 +1,5 @@\n a\n-b\n+\n c\n d\n e\n\
+             @@ -9,4 +9,3 @@\n i\n j\n k\n-l\n"
+        );
+        assert_eq!(unified("src/x.rs", old, Some(old)), "");
+    }
+
+    #[test]
+    fn test_unified_deleted_file_and_inserted_lines() {
+        assert_eq!(unified("gone.py", "x\ny", None), "--- a/gone.py\n+++ /dev/null\n@@ -1,2 +0,0 @@\n-x\n-y\n");
+        assert_eq!(
+            unified("lib.rs", "mod a;\nfn f() {}", Some("mod a {\n    fn g() {}\n}\nfn f() {}")),
+            "--- a/lib.rs\n+++ b/lib.rs\n@@ -1,2 +1,4 @@\n-mod a;\n+mod a {\n+    fn g() {}\n+}\n fn f() {}\n"
+        );
+    }
+}
@@ -8,6 +8,9 @@ pub struct FileState {
     pub path: PathBuf,
     pub original_lines: Vec<String>,
     pub blanked_lines: HashSet<usize>,
+    /// New text for lines that were edited below line level (tokens, characters)
+    pub replaced_lines: HashMap<usize, String>,
+    pub deleted: bool,
 }
 
 impl FileState {
@@ -17,6 +20,8 @@ impl FileState {
             path,
             original_lines,
             blanked_lines: HashSet::new(),
+            replaced_lines: HashMap::new(),
+            deleted: false,
         }
     }
 
@@ -28,23 +33,65 @@ impl FileState {
         }
     }
 
+    #[allow(dead_code)]
     pub fn unblank_lines(&mut self, lines: &[usize]) {
         for &line in lines {
             self.blanked_lines.remove(&line);
         }
     }
 
+    /// Mark the whole file as deleted; it is removed from disk on the next write
+    pub fn delete(&mut self) {
+        self.deleted = true;
+    }
+
+    /// Replace the text of a line; replacing it with only whitespace blanks it
+    pub fn replace_line(&mut self, line: usize, text: String) {
+        if line >= self.original_lines.len() {
+            return;
+        }
+        if text.trim().is_empty() {
+            self.replaced_lines.remove(&line);
+            self.blanked_lines.insert(line);
+        } else {
+            self.blanked_lines.remove(&line);
+            self.replaced_lines.insert(line, text);
+        }
+    }
+
+    /// Replace lines [start_line, end_line) with `text`, which goes on the first line
+    /// while the rest are blanked, so line numbers after the span don't move
+    pub fn replace_span(&mut self, start_line: usize, end_line: usize, text: String) {
+        if start_line >= end_line {
+            return;
+        }
+        self.replace_line(start_line, text);
+        let rest: Vec<usize> = (start_line + 1..end_line).collect();
+        self.blank_lines(&rest);
+    }
+
+    /// Current text of lines [start_line, end_line), joined with newlines
+    pub fn span_text(&self, start_line: usize, end_line: usize) -> String {
+        (start_line..end_line)
+            .map(|i| self.line(i))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Current text of a line: empty if blanked, otherwise its replacement or original text
+    pub fn line(&self, line: usize) -> &str {
+        if self.blanked_lines.contains(&line) {
+            return "";
+        }
+        match self.replaced_lines.get(&line) {
+            Some(text) => text,
+            None => self.original_lines.get(line).map_or("", |text| text.as_str()),
+        }
+    }
+
     pub fn current_content(&self) -> String {
-        self.original_lines
-            .iter()
-            .enumerate()
-            .map(|(i, line)| {
-                if self.blanked_lines.contains(&i) {
-                    String::new()
-                } else {
-                    line.clone()
-                }
-            })
+        (0..self.original_lines.len())
+            .map(|i| self.line(i))
             .collect::<Vec<_>>()
             .join("\n")
     }
@@ -53,16 +100,53 @@ impl FileState {
         self.original_lines.len()
     }
 
+    /// Lines of text left; a replaced line can hold several, e.g. an inlined module
     pub fn non_blank_lines(&self) -> usize {
-        self.original_lines.len() - self.blanked_lines.len()
+        if self.deleted {
+            return 0;
+        }
+        let extra: usize = self
+            .replaced_lines
+            .iter()
+            .filter(|(i, _)| !self.blanked_lines.contains(i))
+            .map(|(_, text)| text.matches('\n').count())
+            .sum();
+        self.original_lines.len() - self.blanked_lines.len() + extra
     }
 
     /// Get list of line indices that are not currently blanked
     pub fn non_blank_line_indices(&self) -> Vec<usize> {
+        if self.deleted {
+            return Vec::new();
+        }
         (0..self.original_lines.len())
             .filter(|i| !self.blanked_lines.contains(i))
             .collect()
     }
+
+    /// Characters left on non-blank lines, to measure reduction below line level
+    pub fn non_blank_chars(&self) -> usize {
+        self.non_blank_line_indices()
+            .into_iter()
+            .map(|i| self.line(i).len())
+            .sum()
+    }
+
+    /// Key identifying the current state of this file, used to avoid retesting states
+    pub fn state_key(&self) -> String {
+        if self.deleted {
+            return format!("{:?}:deleted", self.path);
+        }
+        let mut blanked: Vec<_> = self.blanked_lines.iter().copied().collect();
+        blanked.sort_unstable();
+        let mut replaced: Vec<_> = self
+            .replaced_lines
+            .iter()
+            .filter(|(line, _)| !self.blanked_lines.contains(line))
+            .collect();
+        replaced.sort_unstable();
+        format!("{:?}:{:?}:{:?}", self.path, blanked, replaced)
+    }
 }
 
 pub struct FileManager {
@@ -127,6 +211,19 @@ impl FileManager {
 
     pub fn write_all(&self) -> Result<()> {
         for file_state in self.files.values() {
+            if file_state.deleted {
+                if file_state.path.exists() {
+                    fs::remove_file(&file_state.path)
+                        .with_context(|| format!("Failed to delete file: {:?}", file_state.path))?;
+                }
+                continue;
+            }
+            if let Some(parent) = file_state.path.parent() {
+                if !parent.as_os_str().is_empty() && !parent.exists() {
+                    fs::create_dir_all(parent)
+                        .with_context(|| format!("Failed to create directory: {:?}", parent))?;
+                }
+            }
             let content = file_state.current_content();
             fs::write(&file_state.path, content)
                 .with_context(|| format!("Failed to write file: {:?}", file_state.path))?;
@@ -134,15 +231,26 @@ impl FileManager {
         Ok(())
     }
 
+    #[allow(dead_code)]
     pub fn restore_all(&self) -> Result<()> {
         for file_state in self.files.values() {
             let content = file_state.original_lines.join("\n");
+            if let Some(parent) = file_state.path.parent() {
+                if !parent.as_os_str().is_empty() && !parent.exists() {
+                    fs::create_dir_all(parent)
+                        .with_context(|| format!("Failed to recreate directory: {:?}", parent))?;
+                }
+            }
             fs::write(&file_state.path, content)
                 .with_context(|| format!("Failed to restore file: {:?}", file_state.path))?;
         }
         Ok(())
     }
 
+    pub fn get_file(&self, path: &Path) -> Option<&FileState> {
+        self.files.get(path)
+    }
+
     pub fn get_file_mut(&mut self, path: &Path) -> Option<&mut FileState> {
         self.files.get_mut(path)
     }
@@ -151,6 +259,7 @@ impl FileManager {
         &self.files
     }
 
+    #[allow(dead_code)]
     pub fn total_lines(&self) -> usize {
         self.files.values().map(|f| f.total_lines()).sum()
     }
@@ -159,6 +268,61 @@ impl FileManager {
         self.files.values().map(|f| f.non_blank_lines()).sum()
     }
 
+    pub fn non_blank_chars(&self) -> usize {
+        self.files.values().map(|f| f.non_blank_chars()).sum()
+    }
+
+    pub fn deleted_files(&self) -> usize {
+        self.files.values().filter(|f| f.deleted).count()
+    }
+
+    /// Move every file under `from` to the same place under `to`, e.g. into a git worktree
+    /// Only paths change; files are written at their new location by the next `write_all`.
+    pub fn remap(&mut self, from: &Path, to: &Path) {
+        self.files = std::mem::take(&mut self.files)
+            .into_values()
+            .map(|mut state| {
+                if let Ok(relative) = state.path.strip_prefix(from) {
+                    state.path = to.join(relative);
+                }
+                (state.path.clone(), state)
+            })
+            .collect();
+    }
+
+    /// The current files as changes to `originals`, e.g. to report on a resumed run against the
+    /// files its session started from; originals that are no longer here count as deleted
+    pub fn with_originals(&self, originals: Vec<(PathBuf, String)>) -> FileManager {
+        let mut files = HashMap::new();
+        for (path, content) in originals {
+            let mut state = FileState::new(path.clone(), content);
+            match self.files.get(&path) {
+                Some(current) if !current.deleted => {
+                    for i in 0..state.total_lines() {
+                        let now = current.line(i);
+                        if now.trim().is_empty() {
+                            if !state.original_lines[i].trim().is_empty() {
+                                state.blanked_lines.insert(i);
+                            }
+                        } else if now != state.original_lines[i] {
+                            state.replaced_lines.insert(i, now.to_string());
+                        }
+                    }
+                }
+                _ => state.delete(),
+            }
+            files.insert(path, state);
+        }
+        FileManager { files }
+    }
+
+    /// Key identifying the current state of all files
+    pub fn state_key(&self) -> String {
+        let mut keys: Vec<_> = self.files.values().map(|state| state.state_key()).collect();
+        keys.sort();
+        keys.join("|")
+    }
+
     #[cfg(test)]
     pub fn add_file_from_content(&mut self, path: PathBuf, content: String) {
         self.files.insert(path.clone(), FileState::new(path, content));
@@ -198,6 +362,36 @@ mod tests {
         assert_eq!(state.non_blank_lines(), 1);
     }
 
+    #[test]
+    fn test_file_state_replace_line() {
+        let content = "let x = foo(1, 2);\nline2".to_string();
+        let mut state = FileState::new(PathBuf::from("test.txt"), content);
+        state.replace_line(0, "let x = foo(1);".to_string());
+        assert_eq!(state.line(0), "let x = foo(1);");
+        assert_eq!(state.current_content(), "let x = foo(1);\nline2");
+        assert_eq!(state.non_blank_chars(), 20);
+
+        // Replacing with nothing blanks the line
+        state.replace_line(0, "   ".to_string());
+        assert_eq!(state.non_blank_lines(), 1);
+        assert_eq!(state.current_content(), "\nline2");
+
+        // A line can hold several lines of text
+        state.replace_line(1, "mod m {\n    fn f() {}\n}".to_string());
+        assert_eq!(state.non_blank_lines(), 3);
+    }
+
+    #[test]
+    fn test_file_state_replace_span() {
+        let content = "fn f() -> i32 {\n\n    let x = 1;\n    x\n}".to_string();
+        let mut state = FileState::new(PathBuf::from("test.rs"), content);
+        state.blank_lines(&[1]);
+        state.replace_span(1, 4, "    todo!()".to_string());
+        assert_eq!(state.current_content(), "fn f() -> i32 {\n    todo!()\n\n\n}");
+        assert_eq!(state.span_text(0, 2), "fn f() -> i32 {\n    todo!()");
+        assert_eq!(state.non_blank_lines(), 3);
+    }
+
     #[test]
     fn test_file_manager_add_file() {
         let temp_dir = TempDir::new().unwrap();
@@ -246,4 +440,91 @@ mod tests {
         let content = fs::read_to_string(&file_path).unwrap();
         assert_eq!(content, original);
     }
+
+    #[test]
+    fn test_file_state_delete() {
+        let mut state = FileState::new(PathBuf::from("test.txt"), "line1\nline2".to_string());
+        state.delete();
+        assert_eq!(state.non_blank_lines(), 0);
+        assert!(state.non_blank_line_indices().is_empty());
+        assert_eq!(state.total_lines(), 2);
+    }
+
+    #[test]
+    fn test_file_manager_delete_and_restore() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("sub").join("test.txt");
+        fs::create_dir_all(file_path.parent().unwrap()).unwrap();
+        fs::write(&file_path, "line1\nline2").unwrap();
+
+        let mut manager = FileManager::new();
+        manager.add_file(&file_path).unwrap();
+
+        manager.get_file_mut(&file_path).unwrap().delete();
+        manager.write_all().unwrap();
+        assert!(!file_path.exists());
+        assert_eq!(manager.deleted_files(), 1);
+
+        fs::remove_dir(file_path.parent().unwrap()).unwrap();
+        manager.restore_all().unwrap();
+        assert_eq!(fs::read_to_string(&file_path).unwrap(), "line1\nline2");
+    }
+
+    #[test]
+    fn test_file_manager_state_key() {
+        let mut manager = FileManager::new();
+        let path = PathBuf::from("test.txt");
+        manager.add_file_from_content(path.clone(), "a\nb\nc".to_string());
+        assert_eq!(manager.total_lines(), 3);
+
+        let before = manager.state_key();
+        manager.get_file_mut(&path).unwrap().blank_lines(&[2, 0]);
+        let blanked = manager.state_key();
+        assert_ne!(before, blanked);
+
+        // Blanking order doesn't matter
+        let state = manager.get_file_mut(&path).unwrap();
+        state.unblank_lines(&[0, 2]);
+        state.blank_lines(&[0, 2]);
+        assert_eq!(manager.state_key(), blanked);
+    }
+
+    #[test]
+    fn test_file_manager_remap() {
+        let temp_dir = TempDir::new().unwrap();
+        let from = temp_dir.path().join("checkout");
+        let to = temp_dir.path().join("worktree");
+        let mut manager = FileManager::new();
+        manager.add_file_from_content(from.join("src/main.rs"), "fn main() {}".to_string());
+        let elsewhere = temp_dir.path().join("elsewhere.rs");
+        manager.add_file_from_content(elsewhere.clone(), "x".to_string());
+
+        manager.remap(&from, &to);
+        let moved = to.join("src/main.rs");
+        assert_eq!(manager.get_file(&moved).unwrap().path, moved);
+        assert!(manager.get_file(&elsewhere).is_some());
+
+        // Missing directories are created on write
+        manager.write_all().unwrap();
+        assert_eq!(fs::read_to_string(&moved).unwrap(), "fn main() {}");
+        assert!(!from.exists());
+    }
+
+    #[test]
+    fn test_with_originals() {
+        // What a resumed run scans: blanked lines are empty on disk, and gone.py was deleted
+        let mut current = FileManager::new();
+        current.add_file_from_content(PathBuf::from("a.py"), "import os\n\n\nprint(x=1)".to_string());
+
+        let originals = vec![
+            (PathBuf::from("a.py"), "import os\nimport sys\n\nprint(x = 1)".to_string()),
+            (PathBuf::from("gone.py"), "pass".to_string()),
+        ];
+        let manager = current.with_originals(originals);
+        let a = manager.get_file(Path::new("a.py")).unwrap();
+        assert_eq!(a.blanked_lines, HashSet::from([1]));
+        assert_eq!(a.replaced_lines, HashMap::from([(3, "print(x=1)".to_string())]));
+        assert!(manager.get_file(Path::new("gone.py")).unwrap().deleted);
+        assert_eq!(manager.deleted_files(), 1);
+    }
 }
@@ -0,0 +1,408 @@
+use crate::timestamp;
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Records every accepted chomp as a commit on a `chompie/<timestamp>` branch
+///
+/// Commits are built with plumbing (a private index file, `write-tree`, `commit-tree` and
+/// `update-ref`), so the current branch, the user's index and the working tree are never
+/// touched. The branch starts at HEAD, plus a commit for any uncommitted changes to the
+/// chomped files, so every step of the reduction can be diffed, bisected or checked out.
+pub struct GitHistory {
+    /// Directory git runs in
+    dir: PathBuf,
+    index: PathBuf,
+    branch: String,
+    tip: String,
+    commits: usize,
+    /// Fallback identity when the repository has none configured
+    identity: Vec<(&'static str, &'static str)>,
+}
+
+impl GitHistory {
+    /// Start a branch at HEAD for a chomp of `files` in `directory`
+    pub fn start(directory: &Path, files: &[PathBuf]) -> Result<GitHistory> {
+        let mut history = GitHistory {
+            dir: directory.to_path_buf(),
+            index: PathBuf::new(),
+            branch: format!("chompie/{}", timestamp::now()?),
+            tip: String::new(),
+            commits: 0,
+            identity: Vec::new(),
+        };
+
+        let git_dir = history.git(&["rev-parse", "--absolute-git-dir"]).context("Not a git repository")?;
+        history.index = PathBuf::from(git_dir).join(format!("chompie-index-{}", std::process::id()));
+        history.tip = history.git(&["rev-parse", "--verify", "HEAD^{commit}"]).context("The repository has no commits")?;
+        history.identity = fallback_identity(directory);
+
+        history.git(&["read-tree", &history.tip])?;
+        let tree = history.stage(files)?;
+        let head_tree = history.git(&["rev-parse", &format!("{}^{{tree}}", history.tip)])?;
+        if tree != head_tree {
+            history.commit(&tree, "chompie: starting point\n\nUncommitted changes to the chomped files.")?;
+        }
+        history.git(&["update-ref", &format!("refs/heads/{}", history.branch), &history.tip, ""])?;
+        // Only chomps count
+        history.commits = 0;
+
+        Ok(history)
+    }
+
+    /// Commit the current contents of `paths` on top of the branch
+    pub fn record(&mut self, message: &str, paths: &[PathBuf]) -> Result<()> {
+        let tree = self.stage(paths)?;
+        let previous = self.tip.clone();
+        self.commit(&tree, message)?;
+        self.git(&["update-ref", &format!("refs/heads/{}", self.branch), &self.tip, &previous])?;
+        Ok(())
+    }
+
+    pub fn branch(&self) -> &str {
+        &self.branch
+    }
+
+    pub fn commits(&self) -> usize {
+        self.commits
+    }
+
+    /// Update the private index from the working tree and write its tree
+    fn stage(&self, paths: &[PathBuf]) -> Result<String> {
+        for path in paths {
+            let path = absolute(path)?;
+            let path = path.to_string_lossy();
+            if Path::new(path.as_ref()).exists() {
+                self.git(&["update-index", "--add", "--", &path])?;
+            } else {
+                self.git(&["update-index", "--force-remove", "--", &path])?;
+            }
+        }
+        self.git(&["write-tree"])
+    }
+
+    fn commit(&mut self, tree: &str, message: &str) -> Result<()> {
+        self.tip = self.git(&["commit-tree", tree, "-p", &self.tip, "-m", message])?;
+        self.commits += 1;
+        Ok(())
+    }
+
+    fn git(&self, args: &[&str]) -> Result<String> {
+        let mut command = Command::new("git");
+        command
+            .arg("-C")
+            .arg(&self.dir)
+            .args(args)
+            .env("GIT_INDEX_FILE", &self.index)
+            .envs(self.identity.iter().copied());
+        output(command, args)
+    }
+}
+
+impl Drop for GitHistory {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.index);
+    }
+}
+
+/// What to do with the result of a chomp in a worktree
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Finish {
+    /// Write the chomped files back into the checkout
+    Apply,
+    /// Save the chomped files as a stash entry in the checkout's repository
+    Stash,
+    /// Keep the worktree for the user to inspect or remove
+    Leave,
+}
+
+/// A `git worktree` at HEAD plus the uncommitted changes of the checkout, so a chomp can run
+/// without touching the user's files
+///
+/// Tracked changes, staged or not, are carried over as a patch, and untracked files that
+/// aren't ignored are copied. Ignored files such as build output stay behind.
+pub struct Worktree {
+    /// Top level of the user's checkout
+    checkout: PathBuf,
+    /// Top level of the worktree
+    path: PathBuf,
+}
+
+impl Worktree {
+    /// Create a worktree of the repository `directory` is in, in the temp directory
+    /// Returns None if `directory` isn't in a git repository with at least one commit.
+    pub fn create(directory: &Path) -> Result<Option<Worktree>> {
+        let Ok(checkout) = git(directory, &["rev-parse", "--show-toplevel"]) else {
+            return Ok(None);
+        };
+        if git(directory, &["rev-parse", "--verify", "HEAD^{commit}"]).is_err() {
+            return Ok(None);
+        }
+
+        let checkout = PathBuf::from(checkout).canonicalize()?;
+        let path = std::env::temp_dir().join(format!("chompie-{}-{}", timestamp::now()?, std::process::id()));
+        git(&checkout, &["worktree", "add", "--detach", "--quiet", &path.to_string_lossy(), "HEAD"])
+            .context("Failed to create a git worktree")?;
+        let worktree = Worktree { checkout, path: path.canonicalize()? };
+
+        if let Err(e) = worktree.copy_changes() {
+            worktree.remove()?;
+            return Err(e.context("Failed to copy uncommitted changes into the worktree"));
+        }
+        Ok(Some(worktree))
+    }
+
+    /// A worktree made by an earlier run, to resume or remove it
+    pub fn open(checkout: &Path, path: &Path) -> Worktree {
+        Worktree { checkout: checkout.to_path_buf(), path: path.to_path_buf() }
+    }
+
+    pub fn checkout(&self) -> &Path {
+        &self.checkout
+    }
+
+    fn copy_changes(&self) -> Result<()> {
+        let patch = self.path.with_extension("patch");
+        let patch_arg = format!("--output={}", patch.to_string_lossy());
+        git(&self.checkout, &["diff", "HEAD", "--binary", &patch_arg])?;
+        let applied = if fs::metadata(&patch)?.len() > 0 {
+            git(&self.path, &["apply", "--binary", &patch.to_string_lossy()]).map(drop)
+        } else {
+            Ok(())
+        };
+        fs::remove_file(&patch)?;
+        applied?;
+
+        let untracked = git(&self.checkout, &["ls-files", "--others", "--exclude-standard", "-z"])?;
+        for relative in untracked.split('\0').filter(|relative| !relative.is_empty()) {
+            let target = self.path.join(relative);
+            if let Some(parent) = target.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::copy(self.checkout.join(relative), &target)
+                .with_context(|| format!("Failed to copy {:?}", relative))?;
+        }
+        Ok(())
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Where a path in the checkout lives in the worktree, None if it's outside the repository
+    pub fn map(&self, path: &Path) -> Option<PathBuf> {
+        let absolute = absolute(path).ok()?;
+        let relative = absolute.strip_prefix(&self.checkout).ok()?;
+        if relative.as_os_str().is_empty() {
+            return Some(self.path.clone());
+        }
+        Some(self.path.join(relative))
+    }
+
+    /// Store everything that changed under `directory` in the worktree as a stash entry of
+    /// the checkout's repository
+    /// Returns the stash commit, or None if nothing changed.
+    pub fn stash(&self, directory: &Path, message: &str) -> Result<Option<String>> {
+        let identity = fallback_identity(&self.path);
+        let run = |dir: &Path, args: &[&str]| {
+            let mut command = Command::new("git");
+            command.arg("-C").arg(dir).args(args).envs(identity.iter().copied());
+            output(command, args)
+        };
+
+        run(&self.path, &["add", "--all", "--", &directory.to_string_lossy()])?;
+        let commit = run(&self.path, &["stash", "create", message])?;
+        if commit.is_empty() {
+            return Ok(None);
+        }
+        run(&self.checkout, &["stash", "store", "--message", message, &commit])?;
+        Ok(Some(commit))
+    }
+
+    /// Remove the worktree and its files
+    pub fn remove(&self) -> Result<()> {
+        git(&self.checkout, &["worktree", "remove", "--force", &self.path.to_string_lossy()])?;
+        Ok(())
+    }
+}
+
+/// Files under `directory` with uncommitted changes, untracked files included, as absolute paths
+/// Returns None if `directory` isn't in a git repository.
+pub fn uncommitted_files(directory: &Path) -> Result<Option<Vec<PathBuf>>> {
+    let Ok(checkout) = git(directory, &["rev-parse", "--show-toplevel"]) else {
+        return Ok(None);
+    };
+    let checkout = PathBuf::from(checkout).canonicalize()?;
+    // Without commits everything is uncommitted, so compare against the empty tree
+    let base = git(directory, &["rev-parse", "--verify", "HEAD^{commit}"])
+        .unwrap_or_else(|_| EMPTY_TREE.to_string());
+
+    let changed = git(directory, &["diff", "--name-only", "-z", &base, "--", "."])?;
+    let untracked = git(directory, &["ls-files", "--others", "--exclude-standard", "--full-name", "-z", "--", "."])?;
+    let mut files: Vec<PathBuf> = changed
+        .split('\0')
+        .chain(untracked.split('\0'))
+        .filter(|relative| !relative.is_empty())
+        .map(|relative| checkout.join(relative))
+        .collect();
+    files.sort();
+    files.dedup();
+    Ok(Some(files))
+}
+
+/// Hash of the tree with nothing in it, which every repository has
+const EMPTY_TREE: &str = "4b825dc642cb6eb9a060e54bf8d69288fbee4904";
+
+fn git(dir: &Path, args: &[&str]) -> Result<String> {
+    let mut command = Command::new("git");
+    command.arg("-C").arg(dir).args(args);
+    output(command, args)
+}
+
+/// Run a git command, returning its trimmed stdout or failing with its stderr
+fn output(mut command: Command, args: &[&str]) -> Result<String> {
+    let output = command.output().context("Failed to run git")?;
+    if !output.status.success() {
+        anyhow::bail!("git {} failed: {}", args.join(" "), String::from_utf8_lossy(&output.stderr).trim());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Identity for commits made in `dir` when the repository has none configured
+fn fallback_identity(dir: &Path) -> Vec<(&'static str, &'static str)> {
+    if git(dir, &["var", "GIT_COMMITTER_IDENT"]).is_ok() {
+        return Vec::new();
+    }
+    vec![
+        ("GIT_AUTHOR_NAME", "chompie"),
+        ("GIT_AUTHOR_EMAIL", "chompie@localhost"),
+        ("GIT_COMMITTER_NAME", "chompie"),
+        ("GIT_COMMITTER_EMAIL", "chompie@localhost"),
+    ]
+}
+
+/// Absolute path with symlinks resolved, for files that may no longer exist
+pub fn absolute(path: &Path) -> Result<PathBuf> {
+    if let Ok(path) = path.canonicalize() {
+        return Ok(path);
+    }
+    let parent = path.parent().filter(|p| !p.as_os_str().is_empty()).unwrap_or(Path::new("."));
+    let name = path.file_name().with_context(|| format!("Invalid path {:?}", path))?;
+    Ok(parent.canonicalize()?.join(name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn run(dir: &Path, args: &[&str]) -> String {
+        let output = Command::new("git").arg("-C").arg(dir).args(args).output().unwrap();
+        assert!(output.status.success(), "git {:?}: {}", args, String::from_utf8_lossy(&output.stderr));
+        String::from_utf8_lossy(&output.stdout).trim().to_string()
+    }
+
+    #[test]
+    fn test_git_history() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path();
+        run(dir, &["init", "-q", "-b", "main"]);
+        let keep = dir.join("keep.txt");
+        let gone = dir.join("gone.txt");
+        std::fs::write(&keep, "a\nb\n").unwrap();
+        std::fs::write(&gone, "x\n").unwrap();
+        run(dir, &["add", "."]);
+        run(dir, &["-c", "user.name=t", "-c", "user.email=t@t", "commit", "-q", "-m", "init"]);
+        let head = run(dir, &["rev-parse", "HEAD"]);
+
+        // An uncommitted change becomes the starting point
+        std::fs::write(&keep, "a\nb\nc\n").unwrap();
+        let files = vec![keep.clone(), gone.clone()];
+        let mut history = GitHistory::start(dir, &files).unwrap();
+        assert_eq!(run(dir, &["rev-list", "--count", &format!("{}..{}", head, history.branch())]), "1");
+
+        std::fs::write(&keep, "a\n\nc\n").unwrap();
+        std::fs::remove_file(&gone).unwrap();
+        history.record("chompie: blank b\n\nLines remaining: 2", &files).unwrap();
+        history.record("chompie: nothing", std::slice::from_ref(&keep)).unwrap();
+        assert_eq!(history.commits(), 2);
+
+        let branch = history.branch().to_string();
+        assert_eq!(run(dir, &["show", &format!("{}:keep.txt", branch)]), "a\n\nc");
+        assert!(run(dir, &["ls-tree", "--name-only", &branch]).split_whitespace().eq(["keep.txt"]));
+        assert_eq!(run(dir, &["log", "-1", "--format=%s", &format!("{}~1", branch)]), "chompie: blank b");
+
+        // The current branch and the user's index are untouched
+        assert_eq!(run(dir, &["rev-parse", "HEAD"]), head);
+        assert_eq!(run(dir, &["diff", "--cached", "--name-only"]), "");
+        drop(history);
+        let leftovers = dir.join(".git").read_dir().unwrap().filter(|entry| {
+            entry.as_ref().unwrap().file_name().to_string_lossy().starts_with("chompie-index")
+        });
+        assert_eq!(leftovers.count(), 0);
+    }
+
+    #[test]
+    fn test_worktree() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path();
+        assert!(Worktree::create(dir).unwrap().is_none());
+
+        run(dir, &["init", "-q", "-b", "main"]);
+        std::fs::create_dir(dir.join("sub")).unwrap();
+        std::fs::write(dir.join("sub/a.txt"), "a\n").unwrap();
+        std::fs::write(dir.join(".gitignore"), "*.log\n").unwrap();
+        run(dir, &["add", "."]);
+        run(dir, &["-c", "user.name=t", "-c", "user.email=t@t", "commit", "-q", "-m", "init"]);
+        std::fs::write(dir.join("sub/a.txt"), "a\nb\n").unwrap();
+        std::fs::write(dir.join("new.txt"), "new\n").unwrap();
+        std::fs::write(dir.join("build.log"), "ignored\n").unwrap();
+
+        // Uncommitted and untracked files come along, ignored ones don't
+        let worktree = Worktree::create(&dir.join("sub")).unwrap().unwrap();
+        let path = worktree.path().to_path_buf();
+        assert_eq!(std::fs::read_to_string(path.join("sub/a.txt")).unwrap(), "a\nb\n");
+        assert_eq!(std::fs::read_to_string(path.join("new.txt")).unwrap(), "new\n");
+        assert!(!path.join("build.log").exists());
+        assert_eq!(worktree.map(&dir.join("sub")), Some(path.join("sub")));
+        assert_eq!(worktree.map(dir), Some(path.clone()));
+        assert_eq!(worktree.map(Path::new("/")), None);
+
+        std::fs::write(path.join("sub/a.txt"), "a\n\n").unwrap();
+        assert!(worktree.stash(&path, "chompie result").unwrap().is_some());
+        assert!(run(dir, &["stash", "list"]).contains("chompie result"));
+        assert_eq!(run(dir, &["show", "stash@{0}:sub/a.txt"]), "a");
+
+        // The checkout is untouched
+        assert_eq!(std::fs::read_to_string(dir.join("sub/a.txt")).unwrap(), "a\nb\n");
+        worktree.remove().unwrap();
+        assert!(!path.exists());
+        assert_eq!(run(dir, &["worktree", "list"]).lines().count(), 1);
+    }
+
+    #[test]
+    fn test_uncommitted_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path().canonicalize().unwrap();
+        assert_eq!(uncommitted_files(&dir).unwrap(), None);
+
+        run(&dir, &["init", "-q", "-b", "main"]);
+        std::fs::create_dir(dir.join("src")).unwrap();
+        std::fs::write(dir.join("src/clean.rs"), "a").unwrap();
+        std::fs::write(dir.join("src/dirty.rs"), "a").unwrap();
+        std::fs::write(dir.join("top.rs"), "a").unwrap();
+        run(&dir, &["add", "."]);
+        assert_eq!(uncommitted_files(&dir.join("src")).unwrap().unwrap().len(), 2);
+
+        run(&dir, &["-c", "user.name=t", "-c", "user.email=t@t", "commit", "-q", "-m", "init"]);
+        std::fs::write(dir.join("src/dirty.rs"), "b").unwrap();
+        std::fs::write(dir.join("src/new.rs"), "a").unwrap();
+        std::fs::write(dir.join("top.rs"), "b").unwrap();
+        // Only changes under the directory count
+        assert_eq!(
+            uncommitted_files(&dir.join("src")).unwrap(),
+            Some(vec![dir.join("src/dirty.rs"), dir.join("src/new.rs")])
+        );
+    }
+}
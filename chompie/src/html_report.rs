@@ -0,0 +1,320 @@
+use crate::chomper::Journal;
+use crate::command_runner::RunResult;
+use crate::file_manager::{FileManager, FileState};
+use crate::report::Report;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+/// Strategy colors, assigned in name order
+const PALETTE: [&str; 12] = [
+    "#e6194b", "#3cb44b", "#4363d8", "#f58231", "#911eb4", "#42d4f4", "#f032e6", "#9a6324", "#469990", "#800000",
+    "#808000", "#000075",
+];
+
+const STYLE: &str = "
+body { font-family: sans-serif; margin: 2em; color: #222; }
+h1 { font-size: 1.5em; }
+table.stats td { padding: 0 1em 0 0; }
+pre.output { background: #f4f4f4; padding: 0.5em; max-height: 20em; overflow: auto; }
+.legend span { display: inline-block; margin-right: 1em; }
+.swatch { display: inline-block; width: 0.9em; height: 0.9em; margin-right: 0.3em; vertical-align: middle; }
+.heatmap { display: flex; height: 0.9em; margin: 0.2em 0 0.6em; border: 1px solid #ccc; }
+.heatmap span { display: block; min-width: 1px; }
+.kept { background: #d7f5dd; }
+.blank { background: #f4f4f4; }
+table.source { border-collapse: collapse; font-family: monospace; font-size: 0.9em; width: 100%; }
+table.source td { padding: 0 0.5em; white-space: pre; vertical-align: top; }
+table.source td.number { color: #999; text-align: right; user-select: none; }
+table.source td.by { width: 0.4em; padding: 0; }
+tr.removed td.code { color: #999; }
+ins { text-decoration: none; background: #d7f5dd; display: block; }
+summary { cursor: pointer; font-family: monospace; }
+";
+
+/// What happened to one line
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Status<'a> {
+    /// Empty to begin with
+    Blank,
+    Kept,
+    /// Changed by the strategy, to the text if any is left
+    Changed(&'a str, Option<&'a str>),
+}
+
+/// A self-contained HTML page showing what a run removed: every file with surviving lines
+/// highlighted and removed ones struck through, a heatmap of which strategy removed what,
+/// the baseline output, and lines remaining against candidates tried
+pub fn render(report: &Report, baseline: &RunResult, files: &FileManager, journal: &Journal, root: &Path) -> String {
+    let strategies: BTreeSet<&str> =
+        journal.changed_by.values().flat_map(|lines| lines.values().map(String::as_str)).collect();
+    let colors: BTreeMap<&str, &str> =
+        strategies.iter().zip(PALETTE.iter().cycle()).map(|(strategy, color)| (*strategy, *color)).collect();
+    let mut paths: Vec<&Path> = files.files().keys().map(|path| path.as_path()).collect();
+    paths.sort();
+
+    let mut out = String::from("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n");
+    out.push_str(&format!("<title>chompie: {}</title>\n", escape(&report.command)));
+    out.push_str(&format!("<style>{}</style>\n</head>\n<body>\n", STYLE));
+    out.push_str(&format!("<h1>chompie: <code>{}</code></h1>\n", escape(&report.command)));
+
+    out.push_str("<table class=\"stats\">\n");
+    for (label, value) in [
+        ("Lines", format!("{} → {} ({:.1}% removed)", report.initial_lines, report.final_lines, report.reduction_percent())),
+        ("Files removed", format!("{}/{}", report.files_removed, report.file_count)),
+        ("Successful chomps", report.successful_chomps.to_string()),
+        ("Chomps tested", report.chomps_tested.to_string()),
+        ("Time elapsed", format!("{:.1}s", report.elapsed_secs)),
+    ] {
+        out.push_str(&format!("<tr><td>{}</td><td>{}</td></tr>\n", label, escape(&value)));
+    }
+    out.push_str("</table>\n");
+
+    out.push_str("<h2>Baseline output</h2>\n");
+    out.push_str(&format!("<p>Exit code: {}</p>\n", baseline.exit_code));
+    for (name, output) in [("stdout", &baseline.stdout), ("stderr", &baseline.stderr)] {
+        if !output.is_empty() {
+            out.push_str(&format!("<h3>{}</h3>\n<pre class=\"output\">{}</pre>\n", name, escape(output)));
+        }
+    }
+
+    out.push_str("<h2>Lines remaining</h2>\n");
+    out.push_str(&timeline(report.initial_lines, journal));
+
+    out.push_str("<h2>Removed by strategy</h2>\n<p class=\"legend\">");
+    out.push_str("<span><span class=\"swatch kept\"></span>kept</span>");
+    for (strategy, color) in &colors {
+        out.push_str(&format!(
+            "<span><span class=\"swatch\" style=\"background: {}\"></span>{}</span>",
+            color,
+            escape(strategy)
+        ));
+    }
+    out.push_str("</p>\n");
+    for path in &paths {
+        let state = &files.files()[*path];
+        out.push_str(&format!("<div>{}</div>\n", escape(&display(path, root))));
+        out.push_str(&heatmap(state, journal, &colors));
+    }
+
+    out.push_str("<h2>Files</h2>\n");
+    for path in &paths {
+        let state = &files.files()[*path];
+        out.push_str(&source(state, journal, &colors, &display(path, root)));
+    }
+
+    out.push_str("</body>\n</html>\n");
+    out
+}
+
+fn display(path: &Path, root: &Path) -> String {
+    path.strip_prefix(root).unwrap_or(path).display().to_string()
+}
+
+fn status<'a>(state: &'a FileState, journal: &'a Journal, line: usize) -> Status<'a> {
+    let original = state.original_lines[line].as_str();
+    let changed_by = journal.changed_by.get(&state.path).and_then(|lines| lines.get(&line));
+    match changed_by {
+        Some(strategy) if state.deleted || state.line(line).is_empty() => Status::Changed(strategy, None),
+        Some(strategy) => Status::Changed(strategy, Some(state.line(line))),
+        None if original.trim().is_empty() => Status::Blank,
+        None => Status::Kept,
+    }
+}
+
+/// One strip per file, with a segment per run of lines that share a fate
+fn heatmap(state: &FileState, journal: &Journal, colors: &BTreeMap<&str, &str>) -> String {
+    let mut segments: Vec<(Status, usize)> = Vec::new();
+    for line in 0..state.total_lines() {
+        // Only who changed a line matters here, not what it became
+        let status = match status(state, journal, line) {
+            Status::Changed(strategy, _) => Status::Changed(strategy, None),
+            status => status,
+        };
+        match segments.last_mut() {
+            Some((last, count)) if *last == status => *count += 1,
+            _ => segments.push((status, 1)),
+        }
+    }
+
+    let mut out = String::from("<div class=\"heatmap\">");
+    for (status, count) in segments {
+        out.push_str(&match status {
+            Status::Blank => format!("<span class=\"blank\" style=\"flex-grow: {}\"></span>", count),
+            Status::Kept => format!("<span class=\"kept\" style=\"flex-grow: {}\"></span>", count),
+            Status::Changed(strategy, _) => format!(
+                "<span style=\"flex-grow: {}; background: {}\" title=\"{}\"></span>",
+                count,
+                colors[strategy],
+                escape(strategy)
+            ),
+        });
+    }
+    out.push_str("</div>\n");
+    out
+}
+
+/// The file's original text, surviving lines highlighted and changed ones struck through
+fn source(state: &FileState, journal: &Journal, colors: &BTreeMap<&str, &str>, name: &str) -> String {
+    let deleted_by = match state.deleted {
+        true => journal.changed_by.get(&state.path).and_then(|lines| lines.values().next()),
+        false => None,
+    };
+    let summary = match deleted_by {
+        Some(strategy) => format!("{} (deleted by {})", escape(name), escape(strategy)),
+        None if state.deleted => format!("{} (deleted)", escape(name)),
+        None => format!("{} ({} → {} lines)", escape(name), state.original_lines.len(), state.non_blank_lines()),
+    };
+    let open = if state.deleted { "" } else { " open" };
+    let mut out = format!("<details{}>\n<summary>{}</summary>\n<table class=\"source\">\n", open, summary);
+
+    for (line, original) in state.original_lines.iter().enumerate() {
+        let number = format!("<td class=\"number\">{}</td>", line + 1);
+        let row = match status(state, journal, line) {
+            Status::Blank => format!("<tr>{}<td class=\"by\"></td><td class=\"code\"></td></tr>", number),
+            Status::Kept => format!("<tr class=\"kept\">{}<td class=\"by\"></td><td class=\"code\">{}</td></tr>", number, escape(original)),
+            Status::Changed(strategy, now) => {
+                let replacement = now.map(|now| format!("<ins>{}</ins>", escape(now))).unwrap_or_default();
+                format!(
+                    "<tr class=\"removed\">{}<td class=\"by\" style=\"background: {}\" title=\"{}\"></td><td class=\"code\"><del>{}</del>{}</td></tr>",
+                    number,
+                    colors[strategy],
+                    escape(strategy),
+                    escape(original),
+                    replacement
+                )
+            }
+        };
+        out.push_str(&row);
+        out.push('\n');
+    }
+    out.push_str("</table>\n</details>\n");
+    out
+}
+
+/// An SVG line of lines remaining against candidates tried
+fn timeline(initial_lines: usize, journal: &Journal) -> String {
+    const WIDTH: f64 = 800.0;
+    const HEIGHT: f64 = 200.0;
+    let attempts = journal.attempts.max(1) as f64;
+    let lines = initial_lines.max(1) as f64;
+    let point = |attempt: usize, remaining: usize| {
+        format!("{:.1},{:.1}", attempt as f64 / attempts * WIDTH, HEIGHT - remaining as f64 / lines * HEIGHT)
+    };
+
+    // Steps: the count only drops when a chomp is accepted
+    let mut points = vec![point(0, initial_lines)];
+    let mut remaining = initial_lines;
+    for &(attempt, now) in &journal.timeline {
+        points.push(point(attempt, remaining));
+        points.push(point(attempt, now));
+        remaining = now;
+    }
+    points.push(point(journal.attempts, remaining));
+
+    format!(
+        "<svg width=\"{w}\" height=\"{h}\" viewBox=\"-50 -10 {vw} {vh}\" xmlns=\"http://www.w3.org/2000/svg\">\n\
+         <line x1=\"0\" y1=\"{h}\" x2=\"{w}\" y2=\"{h}\" stroke=\"#999\"/>\n\
+         <line x1=\"0\" y1=\"0\" x2=\"0\" y2=\"{h}\" stroke=\"#999\"/>\n\
+         <text x=\"-5\" y=\"10\" text-anchor=\"end\" font-size=\"12\">{initial}</text>\n\
+         <text x=\"-5\" y=\"{h}\" text-anchor=\"end\" font-size=\"12\">0</text>\n\
+         <text x=\"{w}\" y=\"{label}\" text-anchor=\"end\" font-size=\"12\">{attempts} candidates</text>\n\
+         <polyline fill=\"none\" stroke=\"#4363d8\" stroke-width=\"2\" points=\"{points}\"/>\n\
+         </svg>\n",
+        w = WIDTH,
+        h = HEIGHT,
+        vw = WIDTH + 60.0,
+        vh = HEIGHT + 30.0,
+        label = HEIGHT + 15.0,
+        initial = initial_lines,
+        attempts = journal.attempts,
+        points = points.join(" ")
+    )
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn report() -> Report {
+        Report {
+            command: "cargo test".to_string(),
+            file_count: 2,
+            files_removed: 1,
+            initial_lines: 4,
+            final_lines: 1,
+            initial_chars: 40,
+            final_chars: 10,
+            successful_chomps: 2,
+            chomps_tested: 5,
+            command_runs: 6,
+            rounds: 2,
+            elapsed_secs: 3.0,
+            average_command_secs: 0.5,
+            precheck: None,
+            history: None,
+        }
+    }
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(escape("a < b && \"c\" > d"), "a &lt; b &amp;&amp; &quot;c&quot; &gt; d");
+    }
+
+    #[test]
+    fn test_render() {
+        let mut files = FileManager::new();
+        let lib = PathBuf::from("/repo/src/lib.rs");
+        let util = PathBuf::from("/repo/src/util.rs");
+        files.add_file_from_content(lib.clone(), "fn a() {}\n\nfn b() { 1 + 2 }\nfn c() {}".to_string());
+        files.add_file_from_content(util.clone(), "fn util() {}".to_string());
+        files.get_file_mut(&lib).unwrap().blank_lines(&[3]);
+        files.get_file_mut(&lib).unwrap().replace_line(2, "fn b() { 1 }".to_string());
+        files.get_file_mut(&util).unwrap().delete();
+
+        let mut journal = Journal { attempts: 5, ..Journal::default() };
+        journal.timeline = vec![(2, 3), (5, 1)];
+        journal.changed_by.insert(lib.clone(), BTreeMap::from([(2, "tokens".to_string()), (3, "bisection".to_string())]));
+        journal.changed_by.insert(util.clone(), BTreeMap::from([(0, "file_removal".to_string())]));
+
+        let baseline = RunResult { stdout: "ok <1 test>".to_string(), stderr: String::new(), exit_code: 0 };
+        let html = render(&report(), &baseline, &files, &journal, Path::new("/repo"));
+
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<pre class=\"output\">ok &lt;1 test&gt;</pre>"));
+        assert!(!html.contains("<h3>stderr</h3>"));
+        assert!(html.contains("<summary>src/lib.rs (4 → 3 lines)</summary>"));
+        assert!(html.contains("<summary>src/util.rs (deleted by file_removal)</summary>"));
+        assert!(html.contains("<tr class=\"kept\"><td class=\"number\">1</td><td class=\"by\"></td><td class=\"code\">fn a() {}</td></tr>"));
+        assert!(html.contains("<del>fn b() { 1 + 2 }</del><ins>fn b() { 1 }</ins>"));
+        assert!(html.contains("<del>fn c() {}</del></td>"));
+        // Colors go to strategies in name order
+        assert!(html.contains(&format!("style=\"background: {}\" title=\"bisection\"", PALETTE[0])));
+        assert!(html.contains(&format!("background: {}\" title=\"tokens\"", PALETTE[2])));
+        assert!(html.contains("<polyline fill=\"none\" stroke=\"#4363d8\" stroke-width=\"2\" points=\"0.0,0.0 320.0,0.0 320.0,50.0 800.0,50.0 800.0,150.0 800.0,150.0\"/>"));
+    }
+
+    #[test]
+    fn test_heatmap_merges_runs() {
+        let mut files = FileManager::new();
+        let path = PathBuf::from("a.py");
+        files.add_file_from_content(path.clone(), "a\nb\nc\n\nd".to_string());
+        files.get_file_mut(&path).unwrap().blank_lines(&[1, 2]);
+        let mut journal = Journal::default();
+        journal.changed_by.insert(path.clone(), BTreeMap::from([(1, "hdd".to_string()), (2, "hdd".to_string())]));
+        let colors = BTreeMap::from([("hdd", "#e6194b")]);
+
+        assert_eq!(
+            heatmap(&files.files()[&path], &journal, &colors),
+            "<div class=\"heatmap\">\
+             <span class=\"kept\" style=\"flex-grow: 1\"></span>\
+             <span style=\"flex-grow: 2; background: #e6194b\" title=\"hdd\"></span>\
+             <span class=\"blank\" style=\"flex-grow: 1\"></span>\
+             <span class=\"kept\" style=\"flex-grow: 1\"></span></div>\n"
+        );
+    }
+}
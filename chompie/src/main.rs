@@ -1,26 +1,74 @@
+mod analysis;
+mod backup;
+mod block_tree;
 mod chomper;
 mod command_runner;
+mod coverage;
+mod diff;
 mod file_manager;
+mod git;
+mod html_report;
+mod matrix;
+mod observer;
+mod plan;
+mod precheck;
 mod progress;
+mod report;
+mod safety;
+mod sandbox;
 mod strategies;
 mod strategy;
+mod symbol_index;
+#[cfg(feature = "tree-sitter")]
+mod syntax;
+mod timestamp;
+mod tokens;
+#[cfg(feature = "tui")]
+mod tui;
 
+use analysis::{Analysis, Format};
 use anyhow::{Context, Result};
-use chomper::Chomper;
+use backup::Backup;
+use chomper::{Chomper, Journal};
 use clap::Parser;
 use command_runner::CommandRunner;
 use file_manager::FileManager;
-use strategies::{BisectionStrategy, RandomLinesStrategy, RandomRangesStrategy, UpToNLinesStrategy};
+use git::{Finish, GitHistory, Worktree};
+use matrix::Matrix;
+use observer::Aborted;
+use plan::{Plan, StrategyPlan};
+use precheck::{shell_quote, Precheck};
+use report::{PrecheckSummary, Report};
+use safety::{files_to_touch, Guardrails};
+use sandbox::Sandbox;
+use strategies::{
+    BisectionStrategy, CoverageStrategy, FileRemovalStrategy, GoDeclarationsStrategy, Granularity, HierarchicalStrategy,
+    InlineModulesStrategy, JsImportsStrategy, PreprocessorStrategy, PythonImportsStrategy, RandomLinesStrategy,
+    RandomRangesStrategy, Simplification, SimplifyStrategy, StubStrategy, SymbolsStrategy, TokenStrategy,
+    UpToNLinesStrategy,
+};
 use strategy::Strategy;
-use std::io::{self, Write};
+use std::io::{self, IsTerminal, Write};
+use std::path::{Path, PathBuf};
 
 #[derive(Parser, Debug)]
 #[command(name = "chompie")]
 #[command(about = "Minimize code to the smallest subset that produces the same output", long_about = None)]
+#[command(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
+struct Cli {
+    #[command(subcommand)]
+    subcommand: Option<Subcommand>,
+
+    /// Without a subcommand, `chompie <COMMAND>` is a shorthand for `chompie run <COMMAND>`
+    #[command(flatten)]
+    run: Args,
+}
+
+#[derive(clap::Args, Debug)]
 struct Args {
     /// The command to run (e.g., 'cargo test', 'npm test')
-    #[arg(value_name = "COMMAND")]
-    command: String,
+    #[arg(value_name = "COMMAND", required = true)]
+    command: Option<String>,
 
     /// Directory to chomp (defaults to current directory)
     #[arg(short, long, default_value = ".")]
@@ -30,10 +78,16 @@ struct Args {
     #[arg(short = 'y', long)]
     yes: bool,
 
-    /// Strategies to use (comma-separated: bisection,random_lines,random_ranges,up_to_n_lines)
-    #[arg(long, default_value = "bisection,random_lines,random_ranges")]
+    /// Strategies to use (comma-separated: file_removal,python_imports,js_imports,preprocessor,go_decls,symbols,hdd,tree_sitter,bisection,random_lines,random_ranges,up_to_n_lines)
+    #[arg(long, default_value = "file_removal,python_imports,js_imports,preprocessor,go_decls,symbols,hdd,bisection,random_lines,random_ranges")]
     strategies: String,
 
+    /// Strategies for the fine-grained phase that runs once line-level chomping converges
+    /// (comma-separated: stubs,simplify,simplify_literals,simplify_branches,simplify_args,tokens,chars;
+    /// empty to skip the phase)
+    #[arg(long, default_value = "stubs,simplify,tokens")]
+    fine_strategies: String,
+
     /// Maximum attempts for random strategies
     #[arg(long, default_value = "100")]
     random_attempts: usize,
@@ -42,14 +96,176 @@ struct Args {
     #[arg(long, default_value = "10")]
     window_size: usize,
 
+    /// Cheap check to run before the command; candidates that fail it are skipped.
+    /// `{file}` is replaced by each changed file, and `syntax` uses the built-in parser check.
+    /// Can be given more than once (e.g. --precheck syntax --precheck 'cargo check')
+    #[arg(long, value_name = "CMD")]
+    precheck: Vec<String>,
+
+    /// Once chomping is done, inline the surviving `mod foo;` files of a Rust crate into
+    /// their parents, leaves first, to get a single-file reproduction
+    #[arg(long)]
+    inline_modules: bool,
+
+    /// lcov tracefile written by the command (e.g. via `cargo llvm-cov --lcov`), read after the
+    /// baseline run; enables the coverage strategy, which removes never-executed code first
+    #[arg(long, value_name = "FILE")]
+    lcov: Option<PathBuf>,
+
+    /// Commit each accepted chomp to a new `chompie/<timestamp>` branch, leaving the current
+    /// branch alone, for a reviewable and bisectable history of the reduction
+    #[arg(long)]
+    git_commits: bool,
+
+    /// Chomp the directory in place even if it's in a git repository, instead of in a
+    /// temporary `git worktree` that leaves the checkout's files alone
+    #[arg(long)]
+    in_place: bool,
+
+    /// What to do with the result of a chomp in a git worktree: apply it to the checkout,
+    /// save it as a stash, or leave it in the worktree (asks if not given; applies it with --yes,
+    /// like chomping in place)
+    #[arg(long, value_enum, value_name = "ACTION")]
+    finish: Option<Finish>,
+
+    /// Refuse to chomp a directory with more files than this
+    #[arg(long, value_name = "NUM", default_value = "1000")]
+    max_files: usize,
+
+    /// Chomp files in place even if they have uncommitted changes
+    #[arg(long)]
+    allow_dirty: bool,
+
+    /// Chomp files in place even if they aren't under version control
+    #[arg(long)]
+    allow_no_vcs: bool,
+
+    /// Show the candidates every strategy would try first, with counts, sizes and a runtime
+    /// estimate from one timed baseline run, without chomping anything
+    #[arg(long)]
+    dry_run: bool,
+
+    /// With --dry-run, also save every candidate to this file as tab-separated values
+    #[arg(long, value_name = "FILE", requires = "dry_run")]
+    export_plan: Option<PathBuf>,
+
+    /// Show the run in an interactive terminal UI instead of scrolling text: p pauses,
+    /// s skips the current strategy, q aborts and restores the original files
+    #[arg(long, conflicts_with = "verbose")]
+    tui: bool,
+
     /// Print verbose progress information
     #[arg(short, long)]
     verbose: bool,
 }
 
+#[derive(clap::Subcommand, Debug)]
+enum Subcommand {
+    /// Chomp a directory down to what the command's output needs
+    Run(Args),
+    /// Find the code the command's output doesn't need by chomping a throwaway copy of the
+    /// directory, and report it per file; the original files are left alone
+    Analyze {
+        #[command(flatten)]
+        args: Args,
+
+        /// How to write the report
+        #[arg(long, value_enum, default_value = "text")]
+        format: Format,
+
+        /// Write the report to this file instead of printing it (required for json and sarif)
+        #[arg(short, long, value_name = "FILE")]
+        output: Option<PathBuf>,
+    },
+    /// Analyze each test of a suite on its own, to show which code regions each test needs;
+    /// COMMAND runs one test, with `{test}` standing for its name
+    Matrix {
+        #[command(flatten)]
+        args: Args,
+
+        /// Command that lists the tests, e.g. `cargo test -- --list` or `pytest --collect-only -q`
+        #[arg(long, value_name = "CMD", required_unless_present = "tests", conflicts_with = "tests")]
+        list: Option<String>,
+
+        /// A test to analyze, instead of listing them; can be given more than once
+        #[arg(long = "test", value_name = "NAME")]
+        tests: Vec<String>,
+
+        /// Also save the matrix as JSON to this file
+        #[arg(short, long, value_name = "FILE")]
+        output: Option<PathBuf>,
+    },
+    /// Continue a run from where its files are now, with the same options (the latest run by default)
+    Resume(SessionArgs),
+    /// Put back the files a run changed, from the backup it made before writing anything
+    /// (the latest run by default)
+    Restore(SessionArgs),
+    /// Show the summary of a finished run again (the latest run by default)
+    Report {
+        #[command(flatten)]
+        session: SessionArgs,
+
+        /// Print the path of the run's HTML report instead
+        #[arg(long)]
+        html: bool,
+    },
+    /// Show what a run removed, as a unified diff (the latest run by default)
+    Diff {
+        #[command(flatten)]
+        session: SessionArgs,
+
+        /// Only count the removed and added lines per file
+        #[arg(long)]
+        stat: bool,
+    },
+    /// Delete the backups of a run and any worktree it left behind (the latest run by default)
+    Clean {
+        #[command(flatten)]
+        session: SessionArgs,
+
+        /// Delete every run instead
+        #[arg(long, conflicts_with = "session")]
+        all: bool,
+
+        /// Don't ask before deleting
+        #[arg(short = 'y', long)]
+        yes: bool,
+    },
+    /// List past runs
+    Sessions {
+        /// Directory that was chomped
+        #[arg(short, long, default_value = ".")]
+        directory: String,
+    },
+}
+
+/// Picks a past run
+#[derive(clap::Args, Debug)]
+struct SessionArgs {
+    /// Session, as listed by `chompie sessions`
+    session: Option<String>,
+
+    /// Directory that was chomped
+    #[arg(short, long, default_value = ".")]
+    directory: String,
+}
+
+impl SessionArgs {
+    fn open(&self) -> Result<Backup> {
+        Backup::open(Path::new(&self.directory), self.session.as_deref())
+    }
+}
+
+impl Args {
+    /// The command to chomp with; clap requires it unless another subcommand is given
+    fn command(&self) -> &str {
+        self.command.as_deref().unwrap_or_default()
+    }
+}
+
 fn confirm_chomp() -> Result<bool> {
-    print!("⚠️  WARNING: This will destructively modify files in the current directory!\n");
-    print!("Make sure you have a backup or are using version control.\n");
+    println!("⚠️  WARNING: This will destructively modify the files above!");
+    println!("Make sure you have a backup or are using version control.");
     print!("Continue? [y/N]: ");
     io::stdout().flush()?;
 
@@ -59,16 +275,78 @@ fn confirm_chomp() -> Result<bool> {
     Ok(input.trim().eq_ignore_ascii_case("y"))
 }
 
-fn parse_strategies(strategies_str: &str, random_attempts: usize, window_size: usize) -> Result<Vec<Box<dyn Strategy>>> {
+/// Ask before deleting backups, which may hold the only copy of the original files
+fn confirm_clean(backups: &[Backup]) -> Result<bool> {
+    println!("🗑️  This deletes the backed up originals of:");
+    for backup in backups {
+        println!("  {}", backup.id());
+    }
+    print!("Continue? [y/N]: ");
+    io::stdout().flush()?;
+
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+
+    Ok(input.trim().eq_ignore_ascii_case("y"))
+}
+
+fn choose_finish() -> Result<Finish> {
+    print!("Apply the result to your checkout [a], save it as a stash [s], or leave it in the worktree [l]? [a/s/L]: ");
+    io::stdout().flush()?;
+
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+
+    Ok(match input.trim().to_ascii_lowercase().as_str() {
+        "a" | "apply" => Finish::Apply,
+        "s" | "stash" => Finish::Stash,
+        _ => Finish::Leave,
+    })
+}
+
+fn parse_strategies(
+    strategies_str: &str,
+    random_attempts: usize,
+    window_size: usize,
+    lcov: Option<&Path>,
+) -> Result<Vec<Box<dyn Strategy>>> {
     let mut strategies: Vec<Box<dyn Strategy>> = Vec::new();
 
     for strategy_name in strategies_str.split(',') {
         let strategy_name = strategy_name.trim();
         match strategy_name {
+            "file_removal" => strategies.push(Box::new(FileRemovalStrategy)),
+            "hdd" => strategies.push(Box::new(HierarchicalStrategy)),
+            "python_imports" => strategies.push(Box::new(PythonImportsStrategy)),
+            "js_imports" => strategies.push(Box::new(JsImportsStrategy)),
+            "preprocessor" => strategies.push(Box::new(PreprocessorStrategy)),
+            "go_decls" => strategies.push(Box::new(GoDeclarationsStrategy)),
+            "symbols" => strategies.push(Box::new(SymbolsStrategy)),
+            "coverage" => match lcov {
+                Some(lcov) => strategies.push(Box::new(CoverageStrategy::new(lcov.to_path_buf()))),
+                None => anyhow::bail!("The coverage strategy needs an lcov file, given with --lcov <FILE>"),
+            },
             "bisection" => strategies.push(Box::new(BisectionStrategy)),
             "random_lines" => strategies.push(Box::new(RandomLinesStrategy::new(random_attempts))),
             "random_ranges" => strategies.push(Box::new(RandomRangesStrategy::new(random_attempts))),
             "up_to_n_lines" => strategies.push(Box::new(UpToNLinesStrategy::new(window_size))),
+            "stubs" => strategies.push(Box::new(StubStrategy)),
+            "simplify" => {
+                for strategy in SimplifyStrategy::all() {
+                    strategies.push(Box::new(strategy));
+                }
+            }
+            "simplify_literals" => strategies.push(Box::new(SimplifyStrategy::new(Simplification::Literals))),
+            "simplify_branches" => strategies.push(Box::new(SimplifyStrategy::new(Simplification::Branches))),
+            "simplify_args" => strategies.push(Box::new(SimplifyStrategy::new(Simplification::Arguments))),
+            "tokens" => strategies.push(Box::new(TokenStrategy::new(Granularity::Tokens))),
+            "chars" => strategies.push(Box::new(TokenStrategy::new(Granularity::Chars))),
+            // Inlining merges what survived, so it only runs last, after reduction
+            "inline_modules" => anyhow::bail!("inline_modules runs once chomping is done; use --inline-modules"),
+            #[cfg(feature = "tree-sitter")]
+            "tree_sitter" => strategies.push(Box::new(strategies::TreeSitterStrategy)),
+            #[cfg(not(feature = "tree-sitter"))]
+            "tree_sitter" => anyhow::bail!("The tree_sitter strategy requires building with `--features tree-sitter`"),
             _ => anyhow::bail!("Unknown strategy: {}", strategy_name),
         }
     }
@@ -80,117 +358,880 @@ fn parse_strategies(strategies_str: &str, random_attempts: usize, window_size: u
     Ok(strategies)
 }
 
-fn run_chomp(args: Args) -> Result<()> {
-    // Confirm with user
-    if !args.yes {
-        if !confirm_chomp()? {
-            println!("Chomping cancelled.");
-            return Ok(());
+type Strategies = Vec<Box<dyn Strategy>>;
+
+/// Chomp line by line, then below line level, then inline modules
+/// Returns the number of successful chomps
+fn run_phases(
+    args: &Args,
+    chomper: &mut Chomper,
+    strategies: &[Box<dyn Strategy>],
+    fine_strategies: &[Box<dyn Strategy>],
+    round: &mut usize,
+) -> Result<usize> {
+    let quiet = args.tui;
+    let mut total_successful = 0;
+
+    // Meta-strategy: rotate through all strategies until no more progress, then
+    // shave the surviving lines with the fine-grained strategies. Fine-grained edits can
+    // unlock more line-level chomps, so repeat both until neither makes progress.
+    loop {
+        if !quiet {
+            println!("🍽️  Starting multi-strategy chomping...\n");
+        }
+        total_successful += run_rounds(chomper, strategies, round, quiet)?;
+
+        if fine_strategies.is_empty() {
+            break;
+        }
+
+        if !quiet {
+            println!("🔬 Starting fine-grained chomping...\n");
+        }
+        let fine_successful = run_rounds(chomper, fine_strategies, round, quiet)?;
+        total_successful += fine_successful;
+
+        if fine_successful == 0 {
+            break;
         }
     }
 
-    println!("🍴 Starting chomp process...\n");
+    // Flatten what's left into as few files as possible; each merge is verified like any chomp
+    if args.inline_modules {
+        if !quiet {
+            println!("📦 Inlining modules...\n");
+        }
+        let inline_strategies: Vec<Box<dyn Strategy>> = vec![Box::new(InlineModulesStrategy)];
+        total_successful += run_rounds(chomper, &inline_strategies, round, quiet)?;
+    }
+    Ok(total_successful)
+}
 
-    // Parse strategies
-    let strategies = parse_strategies(&args.strategies, args.random_attempts, args.window_size)?;
+/// The line-level and fine-grained strategies a run uses
+fn configured_strategies(args: &Args, lcov: Option<&Path>) -> Result<(Strategies, Strategies)> {
+    let mut strategies = parse_strategies(&args.strategies, args.random_attempts, args.window_size, lcov)?;
+    // An lcov file is only given to drive the coverage strategy, so make sure it runs first
+    if let Some(lcov) = lcov {
+        if !strategies.iter().any(|s| s.name() == "coverage") {
+            strategies.insert(0, Box::new(CoverageStrategy::new(lcov.to_path_buf())));
+        }
+    }
     println!("📋 Using {} strategies: {}",
         strategies.len(),
         strategies.iter().map(|s| s.name()).collect::<Vec<_>>().join(", ")
     );
+    let fine_strategies = if args.fine_strategies.trim().is_empty() {
+        Vec::new()
+    } else {
+        parse_strategies(&args.fine_strategies, args.random_attempts, args.window_size, lcov)?
+    };
+    if !fine_strategies.is_empty() {
+        println!("🔬 Fine-grained strategies: {}",
+            fine_strategies.iter().map(|s| s.name()).collect::<Vec<_>>().join(", ")
+        );
+    }
+    Ok((strategies, fine_strategies))
+}
+
+/// Rotate through the strategies until a full round makes no progress
+/// Prints nothing if `quiet`, e.g. while the terminal UI owns the screen.
+/// Returns the number of successful chomps
+fn run_rounds(chomper: &mut Chomper, strategies: &[Box<dyn Strategy>], round: &mut usize, quiet: bool) -> Result<usize> {
+    let mut total_successful = 0;
+
+    loop {
+        *round += 1;
+        let mut round_successful = 0;
+
+        chomper.notify_round(*round);
+        if !quiet {
+            println!("--- Round {} ---", round);
+        }
+
+        for strategy in strategies {
+            if !quiet {
+                println!("Trying strategy: {}", strategy.name());
+            }
+
+            let successful = chomper.execute_strategy(strategy.as_ref())?;
+            round_successful += successful;
+            total_successful += successful;
+
+            let current_lines = chomper.file_manager().non_blank_lines();
+            if !quiet {
+                let progress = chomper.progress();
+                println!(
+                    "  Successful chomps: {} | Tried: {}/{} | Current lines: {} | {:.1} attempts/min",
+                    progress.successful_chomps(),
+                    progress.chomps_performed(),
+                    progress.total_ranges(),
+                    current_lines,
+                    progress.attempts_per_minute()
+                );
+            }
+        }
+
+        if !quiet {
+            println!("Round {} complete: {} successful chomps\n", round, round_successful);
+        }
+
+        // If no strategy made progress, we're done
+        if round_successful == 0 {
+            return Ok(total_successful);
+        }
+    }
+}
+
+/// Run a chomp, or continue the run `resumed` backed up
+fn run_chomp(args: Args, resumed: Option<Backup>) -> Result<()> {
+    let guardrails = Guardrails {
+        max_files: args.max_files,
+        allow_dirty: args.allow_dirty,
+        allow_no_vcs: args.allow_no_vcs,
+    };
+    guardrails.check_directory(Path::new(&args.directory))?;
+    check_tui(&args)?;
+    if args.dry_run {
+        return dry_run(&args, &guardrails);
+    }
+
+    // In a git repository, chomp a worktree instead of the user's files. A resumed run picks
+    // up the worktree it left behind, whose files are further along than the checkout's.
+    let left_behind = resumed.as_ref().and_then(left_worktree);
+    let reused = left_behind.is_some();
+    let worktree = match left_behind {
+        Some(worktree) => Some(worktree),
+        None if args.in_place => None,
+        None => Worktree::create(Path::new(&args.directory))?,
+    };
+
+    let Some(worktree) = worktree else {
+        if chomp(&args, &guardrails, None, resumed)?.is_some() {
+            println!("Files have been modified in place.");
+        }
+        return Ok(());
+    };
+
+    match chomp(&args, &guardrails, Some((&worktree, reused)), resumed) {
+        Ok(Some((file_manager, backup))) => finish_worktree(&args, worktree, file_manager, &backup),
+        Ok(None) if reused => Ok(()),
+        Ok(None) => worktree.remove(),
+        Err(e) => {
+            if !reused {
+                worktree.remove()?;
+            }
+            Err(e)
+        }
+    }
+}
+
+/// Print what every strategy would try first, without modifying anything
+/// Runs the command once, in place, to time it and to let it write the lcov file.
+fn dry_run(args: &Args, guardrails: &Guardrails) -> Result<()> {
+    println!("🔍 Dry run: nothing will be modified\n");
+    let (strategies, fine_strategies) = configured_strategies(args, args.lcov.as_deref())?;
 
-    // Set up file manager
     println!("📁 Scanning directory: {}", args.directory);
     let mut file_manager = FileManager::new();
     file_manager
         .add_directory(&args.directory)
         .context("Failed to scan directory")?;
+    let file_count = file_manager.files().len();
+    println!("Found {} files with {} lines\n", file_count, file_manager.non_blank_lines());
+    if file_count == 0 {
+        println!("No files to chomp!");
+        return Ok(());
+    }
+    guardrails.check_file_count(file_count)?;
+
+    println!("⏱️  Timing a baseline run of: '{}'", args.command());
+    let start = std::time::Instant::now();
+    let baseline = CommandRunner::with_verbose(args.command().to_string(), args.verbose).run()?;
+    let baseline_time = start.elapsed();
+    println!("  Exit code: {}", baseline.exit_code);
+    if let Some(lcov) = &args.lcov {
+        check_lcov(lcov)?;
+    }
+    println!();
+
+    let files = file_manager.files();
+    let plan = Plan {
+        strategies: strategies
+            .iter()
+            .map(|strategy| StrategyPlan::new(strategy.as_ref(), false, files))
+            .chain(fine_strategies.iter().map(|strategy| StrategyPlan::new(strategy.as_ref(), true, files)))
+            .collect(),
+        baseline_time,
+    };
+    print!("{}", plan.render(files));
+
+    if let Some(path) = &args.export_plan {
+        plan.export(path, files)?;
+        println!("\n📄 Exported {} candidates to {}", plan.candidates(), path.display());
+    }
+    Ok(())
+}
+
+/// Make sure the baseline run wrote a usable lcov file, so the coverage strategy never runs
+/// blind
+fn check_lcov(lcov: &Path) -> Result<()> {
+    if !lcov.exists() {
+        anyhow::bail!("The baseline run didn't write the lcov file {:?}", lcov);
+    }
+    if coverage::Coverage::load(lcov)?.is_empty() {
+        anyhow::bail!("The lcov file {:?} has no line coverage (DA records) to chomp by", lcov);
+    }
+    Ok(())
+}
+
+/// Refuse --tui without the feature or a terminal to draw on
+fn check_tui(args: &Args) -> Result<()> {
+    if args.tui {
+        if !cfg!(feature = "tui") {
+            anyhow::bail!("--tui requires building with `--features tui`");
+        }
+        if !io::stdout().is_terminal() {
+            anyhow::bail!("--tui needs a terminal");
+        }
+    }
+    Ok(())
+}
+
+/// Guardrails for chomping sandbox copies, after refusing options that only make sense in
+/// place or in a worktree
+fn sandbox_guardrails(args: &Args, subcommand: &str) -> Result<Guardrails> {
+    let unsupported = [
+        ("--in-place", args.in_place),
+        ("--finish", args.finish.is_some()),
+        ("--git-commits", args.git_commits),
+        ("--inline-modules", args.inline_modules),
+        ("--dry-run", args.dry_run),
+    ];
+    if let Some((flag, _)) = unsupported.iter().find(|(_, given)| *given) {
+        anyhow::bail!("{} doesn't apply to {}, which never changes the original files", flag, subcommand);
+    }
+    check_tui(args)?;
+    let guardrails = Guardrails {
+        max_files: args.max_files,
+        allow_dirty: args.allow_dirty,
+        allow_no_vcs: args.allow_no_vcs,
+    };
+    guardrails.check_directory(Path::new(&args.directory))?;
+    Ok(guardrails)
+}
+
+/// Chomp a sandbox copy of the directory and report which lines could go
+fn analyze(args: Args, format: Format, output: Option<&Path>) -> Result<()> {
+    // Progress goes to stdout too, so machine-readable reports need a file of their own
+    if format != Format::Text && output.is_none() {
+        anyhow::bail!("--format {} needs --output <FILE>", format!("{:?}", format).to_lowercase());
+    }
+    let guardrails = sandbox_guardrails(&args, "analyze")?;
+    let directory = Path::new(&args.directory);
+
+    let sandbox = Sandbox::create(directory)?;
+    println!("🔍 Analyzing a sandbox copy at {}; your files are left alone\n", sandbox.path().display());
+    let chomped = chomp_sandbox(&args, args.command(), &guardrails, &sandbox, false);
+    sandbox.remove()?;
+    let Some(chomped) = chomped? else {
+        return Ok(());
+    };
+    let analysis = Analysis::new(args.command(), &chomped.files, &chomped.copy, &git::absolute(directory)?);
+
+    let rendered = analysis.render(format);
+    match output {
+        Some(path) => {
+            std::fs::write(path, rendered).with_context(|| format!("Failed to write the report to {:?}", path))?;
+            println!(
+                "\n📄 {} of {} lines are removable; wrote the report to {}",
+                analysis.removable_lines(),
+                analysis.lines(),
+                path.display()
+            );
+        }
+        None => print!("\n{}", rendered),
+    }
+    Ok(())
+}
+
+/// Analyze every test on its own and show which code regions each one needs
+fn matrix(args: Args, list: Option<&str>, tests: Vec<String>, output: Option<&Path>) -> Result<()> {
+    if !args.command().contains("{test}") {
+        anyhow::bail!("The command needs a {{test}} placeholder for the test to run, e.g. 'cargo test {{test}} -- --exact'");
+    }
+    let guardrails = sandbox_guardrails(&args, "matrix")?;
+    let directory = Path::new(&args.directory);
+
+    let tests = match list {
+        Some(list) => {
+            println!("📋 Listing tests with: '{}'", list);
+            let listed = CommandRunner::with_verbose(list.to_string(), args.verbose).run()?;
+            if listed.exit_code != 0 {
+                anyhow::bail!("Listing the tests failed with exit code {}: {}", listed.exit_code, listed.stderr.trim());
+            }
+            matrix::parse_test_list(&listed.stdout)
+        }
+        None => tests,
+    };
+    if tests.is_empty() {
+        anyhow::bail!("No tests to analyze");
+    }
+    println!("🧪 Analyzing {} tests, one sandbox chomp each\n", tests.len());
+
+    let mut matrix = Matrix::new(args.command(), &git::absolute(directory)?);
+    for (i, test) in tests.iter().enumerate() {
+        let command = args.command().replace("{test}", &shell_quote(test));
+        println!("=== Test {}/{}: {} ===", i + 1, tests.len(), test);
+        let chomped = Sandbox::create(directory).and_then(|sandbox| {
+            let chomped = chomp_sandbox(&args, &command, &guardrails, &sandbox, true);
+            sandbox.remove().and(chomped)
+        });
+        // One test going wrong shouldn't lose what the others found
+        match chomped {
+            // Chomping a failing test would keep whatever reproduces the failure, which says
+            // nothing about the code the test exercises
+            Ok(Some(chomped)) if chomped.exit_code != 0 => {
+                let reason = format!("fails before chomping (exit code {})", chomped.exit_code);
+                println!("⚠️  Skipping {}: it {}", test, reason);
+                matrix.skip(test, &reason);
+            }
+            Ok(Some(chomped)) => matrix.record(test, &chomped.files, &chomped.copy),
+            // The directory is the same for every test
+            Ok(None) => {
+                for test in &tests[i..] {
+                    matrix.skip(test, "no files to analyze");
+                }
+                break;
+            }
+            Err(e) if e.is::<Aborted>() => {
+                println!("🛑 Aborted; showing the tests analyzed so far\n");
+                break;
+            }
+            Err(e) => {
+                println!("⚠️  Skipping {}: {:#}", test, e);
+                matrix.skip(test, &format!("{:#}", e));
+            }
+        }
+        println!();
+    }
+
+    print!("{}", matrix.render());
+    if let Some(path) = output {
+        std::fs::write(path, matrix.render_json()).with_context(|| format!("Failed to write the matrix to {:?}", path))?;
+        println!("\n📄 Saved the matrix to {}", path.display());
+    }
+    Ok(())
+}
+
+/// The files of a sandbox chomp
+struct SandboxChomp {
+    files: FileManager,
+    /// Where the chomped directory's copy is
+    copy: PathBuf,
+    /// The command's exit code before chomping
+    exit_code: i32,
+}
+
+/// Chomp the directory's copy in `sandbox` with `command` until no strategy makes progress
+/// Returns None if there was nothing to chomp. With `only_passing`, a command that fails
+/// before chomping returns its files unchomped.
+fn chomp_sandbox(
+    args: &Args,
+    command: &str,
+    guardrails: &Guardrails,
+    sandbox: &Sandbox,
+    only_passing: bool,
+) -> Result<Option<SandboxChomp>> {
+    let directory = Path::new(&args.directory);
+    let copy = sandbox.map(directory).context("The directory is outside the sandbox")?;
+    // Like a worktree run, the command runs in the sandbox's copy of the current directory
+    let working_dir = sandbox.map(&std::env::current_dir()?).unwrap_or_else(|| copy.clone());
+    let lcov = match &args.lcov {
+        Some(lcov) if lcov.is_relative() => Some(working_dir.join(lcov)),
+        lcov => lcov.clone(),
+    };
+    let (strategies, fine_strategies) = configured_strategies(args, lcov.as_deref())?;
+
+    println!("📁 Scanning directory: {}", args.directory);
+    let mut file_manager = FileManager::new();
+    file_manager.add_directory(directory).context("Failed to scan directory")?;
+    let file_count = file_manager.files().len();
+    println!("Found {} files with {} lines\n", file_count, file_manager.non_blank_lines());
+    if file_count == 0 {
+        println!("No files to analyze!");
+        return Ok(None);
+    }
+    guardrails.check_file_count(file_count)?;
+    file_manager.remap(directory, &copy);
+    // Also brings along files git doesn't know about, like ignored sources
+    file_manager.write_all().context("Failed to copy the files into the sandbox")?;
+
+    let command_runner =
+        CommandRunner::with_verbose(command.to_string(), args.verbose).with_working_dir(Some(working_dir));
+    let mut chomper = Chomper::with_verbose(file_manager, command_runner, args.verbose);
+    chomper.set_progress_bar(!args.verbose && !args.tui);
+    let prechecks: Vec<Precheck> = args.precheck.iter().map(|p| Precheck::parse(p)).collect();
+    if !prechecks.is_empty() {
+        chomper.set_prechecks(prechecks);
+    }
+
+    println!("🎯 Establishing baseline with command: '{}'", command);
+    let exit_code = chomper.establish_baseline()?.exit_code;
+    println!("  Exit code: {}", exit_code);
+    if only_passing && exit_code != 0 {
+        return Ok(Some(SandboxChomp { files: chomper.into_file_manager(), copy, exit_code }));
+    }
+    if let Some(lcov) = &lcov {
+        check_lcov(lcov)?;
+    }
+    println!();
+
+    let mut round = 0;
+    #[cfg(feature = "tui")]
+    if args.tui {
+        chomper.set_observer(Box::new(tui::Tui::start(command, chomper.file_manager())?));
+    }
+    let phases = run_phases(args, &mut chomper, &strategies, &fine_strategies, &mut round);
+    chomper.clear_observer();
+    phases?;
+    println!("✅ No more progress possible. Analysis complete!");
+
+    Ok(Some(SandboxChomp { files: chomper.into_file_manager(), copy, exit_code }))
+}
+
+/// The worktree a run left behind, if it's still there
+fn left_worktree(backup: &Backup) -> Option<Worktree> {
+    let path = PathBuf::from(backup.field("worktree")?);
+    let checkout = PathBuf::from(backup.field("checkout")?);
+    path.is_dir().then(|| Worktree::open(&checkout, &path))
+}
+
+/// Hand the result of a chomp in `worktree` back to the user
+fn finish_worktree(args: &Args, worktree: Worktree, mut file_manager: FileManager, backup: &Backup) -> Result<()> {
+    let directory = Path::new(&args.directory);
+    let chomped = worktree.map(directory).context("The directory is outside the worktree")?;
+    let finish = match args.finish {
+        Some(finish) => finish,
+        // `--yes` has always meant changing the files without asking
+        None if args.yes => Finish::Apply,
+        None => choose_finish()?,
+    };
+
+    if finish != Finish::Leave {
+        backup.record("worktree", "")?;
+    }
+    match finish {
+        Finish::Apply => {
+            file_manager.remap(&chomped, directory);
+            file_manager.write_all().context("Failed to apply the result to the checkout")?;
+            worktree.remove()?;
+            println!("Applied the result to {}", args.directory);
+        }
+        Finish::Stash => {
+            let message = format!("chompie: {}", args.command());
+            match worktree.stash(&chomped, &message)? {
+                Some(_) => println!("Saved the result as a stash; restore it with: git stash apply"),
+                None => println!("Nothing changed, so there's nothing to stash"),
+            }
+            worktree.remove()?;
+        }
+        Finish::Leave => {
+            println!("The result is in {}", chomped.display());
+            println!("Continue with: chompie resume {}", backup.id());
+            println!("Remove it with: chompie clean {}", backup.id());
+        }
+    }
+
+    Ok(())
+}
+
+/// Chomp the directory, in `worktree` if given; its flag says the files are already there
+/// Returns the chomped files and their backup, or None if there was nothing to chomp or the
+/// user cancelled.
+fn chomp(
+    args: &Args,
+    guardrails: &Guardrails,
+    worktree: Option<(&Worktree, bool)>,
+    resumed: Option<Backup>,
+) -> Result<Option<(FileManager, Backup)>> {
+    println!("🍴 Starting chomp process...\n");
+    let (worktree, in_worktree) = match worktree {
+        Some((worktree, in_worktree)) => (Some(worktree), in_worktree),
+        None => (None, false),
+    };
+
+    // The command runs in the worktree's copy of the current directory, or of the chomped
+    // directory if the current one is outside the repository
+    let directory = Path::new(&args.directory);
+    let mut chomped = None;
+    let mut working_dir = None;
+    if let Some(worktree) = worktree {
+        println!("🌳 Chomping a git worktree at {}; your files are left alone", worktree.path().display());
+        let directory = worktree.map(directory).context("The directory is outside the repository")?;
+        working_dir = Some(worktree.map(&std::env::current_dir()?).unwrap_or_else(|| directory.clone()));
+        chomped = Some(directory);
+    }
+
+    // Parse strategies
+    // A relative lcov file is written relative to wherever the command runs
+    let lcov = match (&args.lcov, &working_dir) {
+        (Some(lcov), Some(working_dir)) if lcov.is_relative() => Some(working_dir.join(lcov)),
+        (lcov, _) => lcov.clone(),
+    };
+    let lcov = lcov.as_deref();
+    let (strategies, fine_strategies) = configured_strategies(args, lcov)?;
+
+    // Set up file manager
+    let scanned = match &chomped {
+        Some(chomped) if in_worktree => chomped.as_path(),
+        _ => directory,
+    };
+    println!("📁 Scanning directory: {}", scanned.display());
+    let mut file_manager = FileManager::new();
+    file_manager
+        .add_directory(scanned)
+        .context("Failed to scan directory")?;
 
     let file_count = file_manager.files().len();
     let initial_lines = file_manager.non_blank_lines();
+    let initial_chars = file_manager.non_blank_chars();
 
     println!("Found {} files with {} lines\n", file_count, initial_lines);
 
     if file_count == 0 {
         println!("No files to chomp!");
-        return Ok(());
+        return Ok(None);
+    }
+    guardrails.check_file_count(file_count)?;
+
+    if worktree.is_none() {
+        // Resumed files are chomped, and so uncommitted, but their originals are backed up
+        if resumed.is_none() {
+            guardrails.check_in_place(directory, &file_manager)?;
+        }
+        println!("📝 Files that will be modified in place:");
+        for (path, lines) in files_to_touch(&file_manager) {
+            println!("  {} ({} lines)", path.display(), lines);
+        }
+        println!();
+
+        // Confirm with user
+        if !args.yes && !confirm_chomp()? {
+            println!("Chomping cancelled.");
+            return Ok(None);
+        }
+    }
+
+    let resuming = resumed.is_some();
+    let backup = match resumed {
+        Some(backup) => {
+            println!("⏯️  Resuming session {}\n", backup.id());
+            backup
+        }
+        None => {
+            // Nothing has been written yet, so this is the last chance to save the originals
+            let mut paths: Vec<&Path> = file_manager.files().keys().map(PathBuf::as_path).collect();
+            paths.sort();
+            let backup =
+                Backup::create(directory, args.command(), &paths).context("Failed to back up the files")?;
+            backup.save_args(&std::env::args().skip(1).collect::<Vec<_>>())?;
+            backup.record("cwd", &std::env::current_dir()?.to_string_lossy())?;
+            // A resumed run starts from chomped files, so the report needs these
+            backup.record("file_count", &file_count.to_string())?;
+            backup.record("initial_lines", &initial_lines.to_string())?;
+            backup.record("initial_chars", &initial_chars.to_string())?;
+            let restore_hint = match args.directory.as_str() {
+                "." => format!("chompie restore {}", backup.id()),
+                _ => format!("chompie restore -d {} {}", args.directory, backup.id()),
+            };
+            println!("💾 Backed up {} files to {}", paths.len(), backup.dir().display());
+            println!("   Undo with: {}\n", restore_hint);
+            backup
+        }
+    };
+    if let (Some(worktree), Some(chomped)) = (worktree, &chomped) {
+        // Recorded up front, so an interrupted run can still be resumed or cleaned up
+        backup.record("worktree", &worktree.path().to_string_lossy())?;
+        backup.record("checkout", &worktree.checkout().to_string_lossy())?;
+        backup.record("result", &chomped.to_string_lossy())?;
+    }
+
+    if let Some(chomped) = chomped.as_ref().filter(|_| !in_worktree) {
+        file_manager.remap(directory, chomped);
+        // Also brings along files git doesn't know about, like ignored sources
+        file_manager.write_all().context("Failed to copy the files into the worktree")?;
     }
 
     // Set up command runner
-    let command_runner = CommandRunner::with_verbose(args.command.clone(), args.verbose);
+    let command_runner =
+        CommandRunner::with_verbose(args.command().to_string(), args.verbose).with_working_dir(working_dir);
 
     // Create chomper
     let mut chomper = Chomper::with_verbose(file_manager, command_runner, args.verbose);
+    // Verbose output and the terminal UI have their own ways of showing progress
+    chomper.set_progress_bar(!args.verbose && !args.tui);
+    let journal_path = backup.dir().join("journal");
+    let journal = if journal_path.is_file() { Journal::load(&journal_path)? } else { Journal::default() };
+    chomper.set_journal(journal, journal_path);
+    let prechecks: Vec<Precheck> = args.precheck.iter().map(|p| Precheck::parse(p)).collect();
+    if !prechecks.is_empty() {
+        println!(
+            "🔎 Prechecking candidates with: {}",
+            prechecks.iter().map(|p| p.describe()).collect::<Vec<_>>().join(", ")
+        );
+        chomper.set_prechecks(prechecks);
+    }
+
+    if args.git_commits {
+        let files: Vec<PathBuf> = chomper.file_manager().files().keys().cloned().collect();
+        let git_history = GitHistory::start(chomped.as_deref().unwrap_or(directory), &files)
+            .context("Failed to set up --git-commits")?;
+        println!("📜 Committing accepted chomps to branch {}", git_history.branch());
+        chomper.set_git_history(git_history);
+    }
 
     // Establish baseline
-    println!("🎯 Establishing baseline with command: '{}'", args.command);
+    println!("🎯 Establishing baseline with command: '{}'", args.command());
     let baseline = chomper.establish_baseline()?;
     println!("Baseline established:");
     println!("  Exit code: {}", baseline.exit_code);
     println!("  Stdout length: {} chars", baseline.stdout.len());
     println!("  Stderr length: {} chars", baseline.stderr.len());
+    // Chomping keeps the output, so a resumed run must start from the same one
+    let baseline_hash = backup::hash(format!("{}\0{}\0{}", baseline.exit_code, baseline.stdout, baseline.stderr).as_bytes());
+    match backup.field("baseline") {
+        Some(original) if original != baseline_hash => anyhow::bail!(
+            "The files no longer produce the output session {} started from, e.g. because it was \
+             interrupted mid-candidate; `chompie restore {}` puts the originals back",
+            backup.id(),
+            backup.id()
+        ),
+        Some(_) => {}
+        None => backup.record("baseline", &baseline_hash)?,
+    }
+    if let Some(lcov) = lcov {
+        check_lcov(lcov)?;
+        println!("  Coverage: {:?}", lcov);
+    }
     println!();
 
-    // Meta-strategy: rotate through all strategies until no more progress
-    println!("🍽️  Starting multi-strategy chomping...\n");
-
     let mut round = 0;
-    let mut total_successful = 0;
     let start_time = std::time::Instant::now();
+    #[cfg(feature = "tui")]
+    if args.tui {
+        chomper.set_observer(Box::new(tui::Tui::start(args.command(), chomper.file_manager())?));
+    }
+    let phases = run_phases(args, &mut chomper, &strategies, &fine_strategies, &mut round);
+    // Hand the terminal back before printing anything
+    chomper.clear_observer();
+    if let Err(e) = phases {
+        if !e.is::<Aborted>() {
+            return Err(e);
+        }
+        // Between candidates, so the files on disk are the last accepted state
+        if worktree.is_some() {
+            // The session's backup is in the checkout's .chompie, but its files are as they were
+            println!("🛑 Aborted; your files were never touched");
+            println!("Remove the session with: chompie clean {}", backup.id());
+        } else {
+            let restored = backup.restore()?;
+            println!("🛑 Aborted; restored {} files from session {}", restored, backup.id());
+        }
+        return Ok(None);
+    }
 
-    loop {
-        round += 1;
-        let mut round_successful = 0;
+    println!("✅ No more progress possible. Chomping complete!");
 
-        println!("--- Round {} ---", round);
+    // Final statistics
+    let precheck = (!args.precheck.is_empty()).then(|| {
+        let stats = chomper.precheck_stats();
+        PrecheckSummary {
+            checked: stats.checked,
+            rejected: stats.rejected,
+            cache_hits: stats.cache_hits,
+            time_secs: stats.time.as_secs_f64(),
+            saved_secs: stats.time_saved(chomper.average_command_time()).as_secs_f64(),
+        }
+    });
+    // A resumed run only scanned the files earlier legs left, so count from the session's start
+    let session_count = |key: &str, this_leg: usize| backup.field(key).and_then(|value| value.parse().ok()).unwrap_or(this_leg);
+    let original_files = session_count("file_count", file_count);
+    let files_left = file_count - chomper.file_manager().deleted_files();
+    let mut report = Report {
+        command: args.command().to_string(),
+        file_count: original_files,
+        files_removed: original_files.saturating_sub(files_left),
+        initial_lines: session_count("initial_lines", initial_lines),
+        final_lines: chomper.file_manager().non_blank_lines(),
+        initial_chars: session_count("initial_chars", initial_chars),
+        final_chars: chomper.file_manager().non_blank_chars(),
+        // The journal counts the accepted chomps of every leg, interrupted ones included
+        successful_chomps: chomper.journal().timeline.len(),
+        chomps_tested: chomper.chomps_tested(),
+        command_runs: chomper.command_runs(),
+        rounds: round,
+        elapsed_secs: start_time.elapsed().as_secs_f64(),
+        average_command_secs: chomper.average_command_time().as_secs_f64(),
+        precheck,
+        history: chomper.git_history().map(|history| (history.branch().to_string(), history.commits())),
+    };
+    let report_path = backup.dir().join("report");
+    if resuming {
+        if let Ok(previous) = Report::load(&report_path) {
+            report = report.after(&previous);
+        }
+    }
+    println!("\n{}", report.render().trim_end());
+    report.save(&report_path)?;
 
-        for strategy in &strategies {
-            println!("Trying strategy: {}", strategy.name());
+    let root = chomped.as_deref().unwrap_or(directory);
+    // Show a resumed run against the files its session started from
+    let session_files = if resuming {
+        let originals = backup.files()?.into_iter().map(|(relative, content)| {
+            (root.join(relative), String::from_utf8_lossy(&content).to_string())
+        });
+        Some(chomper.file_manager().with_originals(originals.collect()))
+    } else {
+        None
+    };
+    let html = html_report::render(
+        &report,
+        &baseline,
+        session_files.as_ref().unwrap_or(chomper.file_manager()),
+        chomper.journal(),
+        root,
+    );
+    let html_path = backup.dir().join("report.html");
+    std::fs::write(&html_path, html).context("Failed to write the HTML report")?;
+    println!("\n🖍️  What was removed, file by file: {}", html_path.display());
 
-            let successful = chomper.execute_strategy(strategy.as_ref())?;
-            round_successful += successful;
-            total_successful += successful;
+    println!("\n✅ Chomping complete!");
 
-            let current_lines = chomper.file_manager().non_blank_lines();
-            println!("  Successful chomps: {} | Current lines: {}", successful, current_lines);
+    Ok(Some((chomper.into_file_manager(), backup)))
+}
+
+fn resume(session: &SessionArgs) -> Result<()> {
+    let backup = session.open()?;
+    let saved = std::iter::once("chompie".to_string()).chain(backup.args()?);
+    let cli = Cli::try_parse_from(saved).context("The saved arguments no longer parse")?;
+    let args = match cli.subcommand {
+        Some(Subcommand::Run(args)) => args,
+        None => cli.run,
+        Some(_) => anyhow::bail!("Session {} isn't a run", backup.id()),
+    };
+    // The saved arguments are relative to where the run started
+    if let Some(cwd) = backup.field("cwd") {
+        std::env::set_current_dir(&cwd).with_context(|| format!("Failed to change to {}", cwd))?;
+    }
+    run_chomp(args, Some(backup))
+}
+
+fn restore(session: &SessionArgs) -> Result<()> {
+    let backup = session.open()?;
+    let restored = backup.restore()?;
+    println!("♻️  Restored {} files from session {}", restored, backup.id());
+    Ok(())
+}
+
+fn report(session: &SessionArgs, html: bool) -> Result<()> {
+    let backup = session.open()?;
+    if html {
+        let path = backup.dir().join("report.html");
+        if !path.is_file() {
+            anyhow::bail!("Session {} has no HTML report; it didn't finish", backup.id());
         }
+        println!("{}", path.display());
+        return Ok(());
+    }
+    let report = Report::load(&backup.dir().join("report"))
+        .with_context(|| format!("Session {} has no report; it didn't finish", backup.id()))?;
+    println!("Session: {}", backup.id());
+    println!("Command: {}\n", report.command);
+    print!("{}", report.render());
+    Ok(())
+}
 
-        println!("Round {} complete: {} successful chomps\n", round, round_successful);
+fn diff(session: &SessionArgs, stat: bool) -> Result<()> {
+    let backup = session.open()?;
+    // The chomped files are wherever the run left them
+    let result = backup.field("result").map(PathBuf::from).filter(|result| result.is_dir());
+    let current = result.as_deref().unwrap_or(backup.directory());
 
-        // If no strategy made progress, we're done
-        if round_successful == 0 {
-            println!("✅ No more progress possible. Chomping complete!");
-            break;
+    for (relative, original) in backup.files()? {
+        let original = String::from_utf8_lossy(&original);
+        let now = std::fs::read_to_string(current.join(&relative)).ok();
+        let diff = diff::unified(&relative.to_string_lossy(), &original, now.as_deref());
+        if diff.is_empty() {
+            continue;
+        }
+        if stat {
+            let body = diff.lines().skip(2);
+            let removed = body.clone().filter(|line| line.starts_with('-')).count();
+            let added = body.filter(|line| line.starts_with('+')).count();
+            println!("{} | -{} +{}{}", relative.display(), removed, added, if now.is_none() { " (deleted)" } else { "" });
+        } else {
+            print!("{}", diff);
         }
     }
+    Ok(())
+}
 
-    // Final statistics
-    let final_lines = chomper.file_manager().non_blank_lines();
-    let elapsed = start_time.elapsed();
-    let reduction_percent = if initial_lines > 0 {
-        ((initial_lines - final_lines) as f64 / initial_lines as f64) * 100.0
+fn clean(session: &SessionArgs, all: bool, yes: bool) -> Result<()> {
+    let backups: Vec<Backup> = if all {
+        Backup::sessions(Path::new(&session.directory))?
+            .iter()
+            .map(|listed| Backup::open(Path::new(&session.directory), Some(&listed.id)))
+            .collect::<Result<_>>()?
     } else {
-        0.0
+        vec![session.open()?]
     };
 
-    println!("\n=== Final Results ===");
-    println!("Initial lines: {}", initial_lines);
-    println!("Final lines: {}", final_lines);
-    println!("Reduction: {:.1}%", reduction_percent);
-    println!("Total successful chomps: {}", total_successful);
-    println!("Total chomps tested: {}", chomper.chomps_tested());
-    println!("Rounds: {}", round);
-    println!("Time elapsed: {}s", elapsed.as_secs());
-
-    println!("\n✅ Chomping complete!");
-    println!("Files have been modified in place.");
+    let count = backups.len();
+    if count == 0 {
+        println!("No sessions to remove");
+        return Ok(());
+    }
+    if !yes && !confirm_clean(&backups)? {
+        println!("Cleaning cancelled.");
+        return Ok(());
+    }
+    for backup in backups {
+        if let Some(worktree) = left_worktree(&backup) {
+            worktree.remove()?;
+        }
+        backup.remove()?;
+    }
+    println!("🧹 Removed {} sessions", count);
+    Ok(())
+}
 
+fn list_sessions(directory: &str) -> Result<()> {
+    let sessions = Backup::sessions(Path::new(directory))?;
+    if sessions.is_empty() {
+        println!("No sessions in {}", Path::new(directory).join(backup::BACKUP_DIR).display());
+    }
+    for session in sessions {
+        println!("{}  {:>4} files  {}", session.id, session.files, session.command);
+    }
     Ok(())
 }
 
 fn main() {
-    let args = Args::parse();
+    let cli = Cli::parse();
 
-    if let Err(e) = run_chomp(args) {
+    let result = match cli.subcommand {
+        Some(Subcommand::Run(args)) => run_chomp(args, None),
+        Some(Subcommand::Analyze { args, format, output }) => analyze(args, format, output.as_deref()),
+        Some(Subcommand::Matrix { args, list, tests, output }) => matrix(args, list.as_deref(), tests, output.as_deref()),
+        Some(Subcommand::Resume(session)) => resume(&session),
+        Some(Subcommand::Restore(session)) => restore(&session),
+        Some(Subcommand::Report { session, html }) => report(&session, html),
+        Some(Subcommand::Diff { session, stat }) => diff(&session, stat),
+        Some(Subcommand::Clean { session, all, yes }) => clean(&session, all, yes),
+        Some(Subcommand::Sessions { directory }) => list_sessions(&directory),
+        None => run_chomp(cli.run, None),
+    };
+    if let Err(e) = result {
         eprintln!("Error: {:#}", e);
         std::process::exit(1);
     }
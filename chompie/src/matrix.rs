@@ -0,0 +1,304 @@
+use crate::analysis::json_string;
+use crate::file_manager::FileManager;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+/// Test names in the output of a listing command: `cargo test -- --list`, `pytest
+/// --collect-only -q`, `go test -list .`, or anything that prints one name per line
+pub fn parse_test_list(output: &str) -> Vec<String> {
+    let lines: Vec<&str> = output.lines().map(str::trim).filter(|line| !line.is_empty()).collect();
+
+    // libtest prints `name: test`, followed by a count
+    let cargo: Vec<String> = lines
+        .iter()
+        .filter_map(|line| line.strip_suffix(": test").or_else(|| line.strip_suffix(": bench")))
+        .map(str::to_string)
+        .collect();
+    if !cargo.is_empty() {
+        return cargo;
+    }
+
+    // pytest prints node ids like `tests/test_a.py::test_x`, followed by a summary
+    let pytest: Vec<String> = lines.iter().filter(|line| line.contains("::")).map(|line| line.to_string()).collect();
+    if !pytest.is_empty() {
+        return pytest;
+    }
+
+    // go prints `ok <package> <time>` after the names; an empty cargo or pytest listing
+    // still ends in its summary
+    lines
+        .into_iter()
+        .filter(|line| !line.starts_with("ok ") && !line.starts_with("ok\t") && !is_summary(line))
+        .map(str::to_string)
+        .collect()
+}
+
+/// libtest's `N tests, N benchmarks` count, or a pytest summary like `no tests collected in 0.01s`
+fn is_summary(line: &str) -> bool {
+    let counts = |part: &str, noun: &str| {
+        let (count, word) = part.trim().split_once(' ').unwrap_or(("", ""));
+        !count.is_empty() && count.chars().all(|c| c.is_ascii_digit()) && word.trim_end_matches('s') == noun
+    };
+    let cargo = line.split_once(", ").is_some_and(|(tests, benches)| counts(tests, "test") && counts(benches, "benchmark"));
+    let pytest = ["collected", "no tests ran"].iter().any(|summary| line.contains(summary));
+    cargo || pytest
+}
+
+/// What one test needed
+pub struct TestNeeds {
+    pub name: String,
+    /// Non-blank lines per file that survived chomping, 1-based; None if the test was skipped
+    pub needed: Option<BTreeMap<PathBuf, BTreeSet<usize>>>,
+    /// Why the test was skipped
+    pub skipped: Option<String>,
+}
+
+impl TestNeeds {
+    pub fn lines(&self) -> usize {
+        self.needed.iter().flat_map(|needed| needed.values()).map(BTreeSet::len).sum()
+    }
+}
+
+/// Consecutive lines of one file needed by the same tests; blank lines don't split a region
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Region {
+    pub path: PathBuf,
+    /// 1-based and inclusive
+    pub start: usize,
+    pub end: usize,
+    /// Indices into `Matrix::tests`
+    pub tests: Vec<usize>,
+}
+
+/// Code regions against the tests that need them, from chomping once per test
+pub struct Matrix {
+    /// The command with its `{test}` placeholder
+    pub template: String,
+    /// The analyzed directory, absolute
+    pub root: PathBuf,
+    /// Non-blank lines of every file, 1-based
+    lines: BTreeMap<PathBuf, Vec<usize>>,
+    pub tests: Vec<TestNeeds>,
+}
+
+impl Matrix {
+    pub fn new(template: &str, root: &Path) -> Self {
+        Matrix { template: template.to_string(), root: root.to_path_buf(), lines: BTreeMap::new(), tests: Vec::new() }
+    }
+
+    /// Record what survived chomping for `name`, from the files under `root`
+    pub fn record(&mut self, name: &str, files: &FileManager, root: &Path) {
+        let mut needed = BTreeMap::new();
+        for state in files.files().values() {
+            let relative = state.path.strip_prefix(root).unwrap_or(&state.path).to_path_buf();
+            let text: Vec<usize> = (0..state.original_lines.len())
+                .filter(|&i| !state.original_lines[i].trim().is_empty())
+                .collect();
+            let kept: BTreeSet<usize> = if state.deleted {
+                BTreeSet::new()
+            } else {
+                text.iter().filter(|&i| !state.blanked_lines.contains(i)).map(|i| i + 1).collect()
+            };
+            self.lines.entry(relative.clone()).or_insert_with(|| text.iter().map(|i| i + 1).collect());
+            needed.insert(relative, kept);
+        }
+        self.tests.push(TestNeeds { name: name.to_string(), needed: Some(needed), skipped: None });
+    }
+
+    /// Record a test that couldn't be analyzed, and why
+    pub fn skip(&mut self, name: &str, reason: &str) {
+        self.tests.push(TestNeeds { name: name.to_string(), needed: None, skipped: Some(reason.to_string()) });
+    }
+
+    pub fn regions(&self) -> Vec<Region> {
+        let mut regions: Vec<Region> = Vec::new();
+        for (path, lines) in &self.lines {
+            let mut open = false;
+            for &line in lines {
+                let tests: Vec<usize> = self
+                    .tests
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, test)| {
+                        test.needed.as_ref().is_some_and(|needed| needed.get(path).is_some_and(|kept| kept.contains(&line)))
+                    })
+                    .map(|(i, _)| i)
+                    .collect();
+                match regions.last_mut() {
+                    Some(region) if open && region.tests == tests => region.end = line,
+                    _ => regions.push(Region { path: path.clone(), start: line, end: line, tests }),
+                }
+                open = true;
+            }
+        }
+        regions
+    }
+
+    pub fn render(&self) -> String {
+        let mut out = String::from("=== Test Necessity Matrix ===\n");
+        out.push_str(&format!("Command: {}\n\nTests:\n", self.template));
+        for (i, test) in self.tests.iter().enumerate() {
+            match &test.skipped {
+                None => out.push_str(&format!("  {:>3}  {} ({} lines)\n", i + 1, test.name, test.lines())),
+                Some(reason) => out.push_str(&format!("  {:>3}  {} (skipped: {})\n", i + 1, test.name, reason)),
+            }
+        }
+
+        let regions = self.regions();
+        let labels: Vec<String> = regions.iter().map(label).collect();
+        let width = labels.iter().map(String::len).max().unwrap_or(0).max("Region".len());
+        let columns: Vec<String> = (1..=self.tests.len()).map(|i| i.to_string()).collect();
+        out.push_str(&format!("\n{:<width$}  {}\n", "Region", columns.join(" "), width = width));
+        for (region, label) in regions.iter().zip(&labels) {
+            let cells: Vec<String> = columns
+                .iter()
+                .enumerate()
+                .map(|(i, column)| {
+                    let cell = match (&self.tests[i].needed, region.tests.contains(&i)) {
+                        (None, _) => "-",
+                        (_, true) => "x",
+                        (_, false) => ".",
+                    };
+                    format!("{:>width$}", cell, width = column.len())
+                })
+                .collect();
+            out.push_str(&format!("{:<width$}  {}\n", label, cells.join(" "), width = width));
+        }
+
+        let analyzed = self.tests.iter().filter(|test| test.needed.is_some()).count();
+        let region_lines = |keep: &dyn Fn(&Region) -> bool| -> usize {
+            regions
+                .iter()
+                .filter(|region| keep(region))
+                .map(|region| self.lines[&region.path].iter().filter(|&&line| line >= region.start && line <= region.end).count())
+                .sum()
+        };
+        out.push_str(&format!(
+            "\nLines no test needs: {}\nLines every test needs: {}\n",
+            region_lines(&|region| region.tests.is_empty()),
+            region_lines(&|region| analyzed > 0 && region.tests.len() == analyzed)
+        ));
+        out
+    }
+
+    /// The tests and regions as JSON, with regions naming the tests that need them
+    pub fn render_json(&self) -> String {
+        let tests: Vec<String> = self
+            .tests
+            .iter()
+            .map(|test| {
+                format!(
+                    "    {{\"name\": {}, \"skipped\": {}, \"reason\": {}, \"lines\": {}}}",
+                    json_string(&test.name),
+                    test.skipped.is_some(),
+                    test.skipped.as_deref().map_or("null".to_string(), json_string),
+                    test.lines()
+                )
+            })
+            .collect();
+        let regions: Vec<String> = self
+            .regions()
+            .iter()
+            .map(|region| {
+                let names: Vec<String> = region.tests.iter().map(|&i| json_string(&self.tests[i].name)).collect();
+                format!(
+                    "    {{\"path\": {}, \"start\": {}, \"end\": {}, \"tests\": [{}]}}",
+                    json_string(&region.path.to_string_lossy().replace('\\', "/")),
+                    region.start,
+                    region.end,
+                    names.join(", ")
+                )
+            })
+            .collect();
+        format!(
+            "{{\n  \"command\": {},\n  \"root\": {},\n  \"tests\": [\n{}\n  ],\n  \"regions\": [\n{}\n  ]\n}}\n",
+            json_string(&self.template),
+            json_string(&self.root.to_string_lossy()),
+            tests.join(",\n"),
+            regions.join(",\n")
+        )
+    }
+}
+
+fn label(region: &Region) -> String {
+    if region.start == region.end {
+        format!("{}:{}", region.path.display(), region.start)
+    } else {
+        format!("{}:{}-{}", region.path.display(), region.start, region.end)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_test_list() {
+        let cargo = "tests::a: test\ntests::b: test\nbench_x: bench\n\n3 tests, 0 benchmarks\n";
+        assert_eq!(parse_test_list(cargo), vec!["tests::a", "tests::b", "bench_x"]);
+
+        let pytest = "tests/test_a.py::test_x\ntests/test_a.py::test_y[1-2]\n\n2 tests collected in 0.01s\n";
+        assert_eq!(parse_test_list(pytest), vec!["tests/test_a.py::test_x", "tests/test_a.py::test_y[1-2]"]);
+
+        let go = "TestAdd\nTestSub\nok  \texample.com/calc\t0.002s\n";
+        assert_eq!(parse_test_list(go), vec!["TestAdd", "TestSub"]);
+    }
+
+    #[test]
+    fn test_parse_test_list_skips_libtest_count() {
+        // With nothing listed, only the count is left for the fallback
+        assert!(parse_test_list("0 tests, 0 benchmarks\n").is_empty());
+        assert!(parse_test_list("1 test, 1 benchmark\n").is_empty());
+    }
+
+    #[test]
+    fn test_parse_test_list_skips_pytest_summary() {
+        assert!(parse_test_list("\nno tests collected in 0.01s\n").is_empty());
+        assert!(parse_test_list("collected 0 items\n\nno tests ran in 0.01s\n").is_empty());
+    }
+
+    fn matrix() -> Matrix {
+        let root = Path::new("/sandbox");
+        let content = "import os\ndef add():\n    pass\n\ndef sub():\n    pass\nunused = 1";
+        let chomped = |blanked: &[usize]| {
+            let mut files = FileManager::new();
+            files.add_file_from_content(root.join("calc.py"), content.to_string());
+            files.get_file_mut(&root.join("calc.py")).unwrap().blank_lines(blanked);
+            files
+        };
+
+        let mut matrix = Matrix::new("pytest {test}", Path::new("/project"));
+        matrix.record("test_add", &chomped(&[0, 4, 5, 6]), root);
+        matrix.record("test_sub", &chomped(&[0, 1, 2, 6]), root);
+        matrix.skip("test_broken", "fails before chomping (exit code 1)");
+        matrix
+    }
+
+    #[test]
+    fn test_regions() {
+        let matrix = matrix();
+        assert_eq!(matrix.tests[0].lines(), 2);
+        assert_eq!(matrix.tests[2].lines(), 0);
+        let regions: Vec<(usize, usize, Vec<usize>)> =
+            matrix.regions().into_iter().map(|region| (region.start, region.end, region.tests)).collect();
+        // The blank line 4 doesn't split 3 from 5
+        assert_eq!(regions, vec![(1, 1, vec![]), (2, 3, vec![0]), (5, 6, vec![1]), (7, 7, vec![])]);
+    }
+
+    #[test]
+    fn test_render() {
+        let matrix = matrix();
+        let text = matrix.render();
+        assert!(text.starts_with("=== Test Necessity Matrix ===\nCommand: pytest {test}\n"));
+        assert!(text.contains("    1  test_add (2 lines)\n"));
+        assert!(text.contains("    3  test_broken (skipped: fails before chomping (exit code 1))\n"));
+        assert!(text.contains("Region       1 2 3\ncalc.py:1    . . -\ncalc.py:2-3  x . -\n"));
+        assert!(text.ends_with("Lines no test needs: 2\nLines every test needs: 0\n"));
+
+        let json = matrix.render_json();
+        assert!(json.contains("{\"name\": \"test_broken\", \"skipped\": true, \"reason\": \"fails before chomping (exit code 1)\", \"lines\": 0}"));
+        assert!(json.contains("{\"name\": \"test_add\", \"skipped\": false, \"reason\": null, \"lines\": 2}"));
+        assert!(json.contains("{\"path\": \"calc.py\", \"start\": 5, \"end\": 6, \"tests\": [\"test_sub\"]}"));
+        assert_eq!(json.matches('[').count(), json.matches(']').count());
+    }
+}
@@ -0,0 +1,58 @@
+use crate::file_manager::FileManager;
+use crate::strategy::Chomp;
+use std::fmt;
+
+/// What became of one candidate
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    /// The output matched, so the chomp was kept
+    Kept,
+    /// The output differed, so the chomp was rolled back
+    Rejected,
+    /// Rolled back without running the command: a no-op, an already-tested state, or vetoed
+    /// by the strategy or a precheck
+    Skipped,
+}
+
+/// What to do before the next candidate
+#[cfg_attr(not(feature = "tui"), allow(dead_code))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Control {
+    Continue,
+    /// Drop the rest of the current strategy's candidates
+    SkipStrategy,
+    /// Stop chomping; the run fails with `Aborted`
+    Abort,
+}
+
+/// Watches a chomp as it happens, e.g. to draw it, and can steer it between candidates
+pub trait Observer {
+    fn round_started(&mut self, _round: usize) {}
+
+    fn strategy_started(&mut self, _name: &str, _candidates: usize, _files: &FileManager) {}
+
+    /// Called before the candidate is applied, so `files` doesn't include it yet
+    fn attempt_started(&mut self, _chomp: &Chomp, _files: &FileManager) {}
+
+    fn attempt_finished(&mut self, _chomp: &Chomp, _outcome: Outcome, _files: &FileManager) {}
+
+    /// A warning the run would otherwise print, e.g. a chomp that failed with an error
+    fn warning(&mut self, _message: &str) {}
+
+    /// Asked before every candidate; may block, e.g. while paused
+    fn control(&mut self) -> Control {
+        Control::Continue
+    }
+}
+
+/// The error a run fails with when an observer aborts it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Aborted;
+
+impl fmt::Display for Aborted {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Chomping was aborted")
+    }
+}
+
+impl std::error::Error for Aborted {}
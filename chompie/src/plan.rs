@@ -0,0 +1,195 @@
+use crate::file_manager::FileState;
+use crate::progress::format_duration;
+use crate::strategy::{Chomp, Strategy};
+use anyhow::{Context, Result};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// The candidates one strategy proposes for the current files, for `--dry-run`
+pub struct StrategyPlan {
+    pub name: String,
+    /// Whether the strategy runs in the fine-grained phase, after line-level chomping converges
+    pub fine: bool,
+    pub chomps: Vec<Chomp>,
+}
+
+impl StrategyPlan {
+    pub fn new(strategy: &dyn Strategy, fine: bool, files: &HashMap<PathBuf, FileState>) -> Self {
+        StrategyPlan { name: strategy.name().to_string(), fine, chomps: strategy.generate_chomps(files) }
+    }
+}
+
+/// Every candidate the configured strategies would try first, without running any of them
+pub struct Plan {
+    pub strategies: Vec<StrategyPlan>,
+    /// How long the baseline run took; each candidate costs about one run
+    pub baseline_time: Duration,
+}
+
+/// Lines a chomp touches: blanked, rewritten or in deleted files
+pub fn chomp_lines(chomp: &Chomp, files: &HashMap<PathBuf, FileState>) -> usize {
+    match chomp {
+        Chomp::Range(range) => range.end_line - range.start_line,
+        Chomp::DeleteFiles(paths) => paths.iter().filter_map(|path| files.get(path)).map(FileState::non_blank_lines).sum(),
+        Chomp::Edit(_) => 1,
+        Chomp::Replace(span) => span.end_line - span.start_line,
+        Chomp::Compound(parts) => parts.iter().map(|part| chomp_lines(part, files)).sum(),
+    }
+}
+
+/// Files a chomp touches
+fn chomp_files(chomp: &Chomp) -> Vec<&Path> {
+    match chomp {
+        Chomp::Range(range) => vec![range.file.as_path()],
+        Chomp::DeleteFiles(paths) => paths.iter().map(PathBuf::as_path).collect(),
+        Chomp::Edit(edit) => vec![edit.file.as_path()],
+        Chomp::Replace(span) => vec![span.file.as_path()],
+        Chomp::Compound(parts) => {
+            let mut files: Vec<&Path> = parts.iter().flat_map(chomp_files).collect();
+            files.sort();
+            files.dedup();
+            files
+        }
+    }
+}
+
+/// Histogram bucket of a size: 1, 2-3, 4-7, 8-15, ...
+fn bucket(lines: usize) -> (usize, usize) {
+    if lines == 0 {
+        return (0, 0);
+    }
+    let low = 1 << (usize::BITS - 1 - lines.leading_zeros());
+    (low, low * 2 - 1)
+}
+
+impl Plan {
+    pub fn candidates(&self) -> usize {
+        self.strategies.iter().map(|strategy| strategy.chomps.len()).sum()
+    }
+
+    /// Time to try every candidate once, one command run each
+    pub fn estimate(&self) -> Duration {
+        self.baseline_time * self.candidates() as u32
+    }
+
+    pub fn render(&self, files: &HashMap<PathBuf, FileState>) -> String {
+        let mut out = String::from("=== Dry Run ===\n");
+        for strategy in &self.strategies {
+            let fine = if strategy.fine { " (fine-grained)" } else { "" };
+            out.push_str(&format!("\n{}{}: {} candidates\n", strategy.name, fine, strategy.chomps.len()));
+            if strategy.chomps.is_empty() {
+                continue;
+            }
+
+            let mut histogram: BTreeMap<(usize, usize), usize> = BTreeMap::new();
+            let mut per_file: BTreeMap<&Path, usize> = BTreeMap::new();
+            for chomp in &strategy.chomps {
+                *histogram.entry(bucket(chomp_lines(chomp, files))).or_default() += 1;
+                for file in chomp_files(chomp) {
+                    *per_file.entry(file).or_default() += 1;
+                }
+            }
+
+            out.push_str("  Lines per candidate:\n");
+            let widest = histogram.values().copied().max().unwrap_or(1);
+            for ((low, high), count) in &histogram {
+                let label = if low == high { low.to_string() } else { format!("{}-{}", low, high) };
+                let bar = "#".repeat((count * 30).div_ceil(widest));
+                out.push_str(&format!("    {:>11} {:>6} {}\n", label, count, bar));
+            }
+            out.push_str("  Candidates per file:\n");
+            for (file, count) in per_file {
+                out.push_str(&format!("    {:>6} {}\n", count, file.display()));
+            }
+        }
+
+        out.push_str(&format!("\nTotal candidates: {}\n", self.candidates()));
+        out.push_str(&format!("Baseline run: {}\n", format_duration(self.baseline_time)));
+        out.push_str(&format!(
+            "Estimated time for one pass: {} (one run per candidate; accepted chomps change the next rounds)\n",
+            format_duration(self.estimate())
+        ));
+        out
+    }
+
+    /// Save every candidate as a tab-separated line: strategy, lines, files, description
+    pub fn export(&self, path: &Path, files: &HashMap<PathBuf, FileState>) -> Result<()> {
+        let mut content = String::from("strategy\tlines\tfiles\tchomp\n");
+        for strategy in &self.strategies {
+            for chomp in &strategy.chomps {
+                let touched: Vec<String> = chomp_files(chomp).iter().map(|file| file.display().to_string()).collect();
+                content.push_str(&format!(
+                    "{}\t{}\t{}\t{}\n",
+                    strategy.name,
+                    chomp_lines(chomp, files),
+                    touched.join(","),
+                    chomp.to_string().replace(['\t', '\n'], " ")
+                ));
+            }
+        }
+        fs::write(path, content).with_context(|| format!("Failed to export the plan to {:?}", path))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::strategies::{BisectionStrategy, FileRemovalStrategy};
+    use crate::strategy::ChompRange;
+
+    fn files() -> HashMap<PathBuf, FileState> {
+        let mut files = HashMap::new();
+        for (name, content) in [("a.py", "1\n2\n3\n4\n5\n6\n7\n8"), ("b.py", "x\ny")] {
+            files.insert(PathBuf::from(name), FileState::new(PathBuf::from(name), content.to_string()));
+        }
+        files
+    }
+
+    #[test]
+    fn test_bucket() {
+        assert_eq!(bucket(1), (1, 1));
+        assert_eq!(bucket(3), (2, 3));
+        assert_eq!(bucket(4), (4, 7));
+        assert_eq!(bucket(100), (64, 127));
+    }
+
+    #[test]
+    fn test_chomp_lines_and_files() {
+        let files = files();
+        let range = Chomp::Range(ChompRange { file: PathBuf::from("a.py"), start_line: 2, end_line: 6 });
+        assert_eq!(chomp_lines(&range, &files), 4);
+        let delete = Chomp::DeleteFiles(vec![PathBuf::from("a.py"), PathBuf::from("b.py")]);
+        assert_eq!(chomp_lines(&delete, &files), 10);
+        let compound = Chomp::Compound(vec![range, delete]);
+        assert_eq!(chomp_files(&compound), vec![Path::new("a.py"), Path::new("b.py")]);
+    }
+
+    #[test]
+    fn test_render_and_export() {
+        let files = files();
+        let plan = Plan {
+            strategies: vec![
+                StrategyPlan::new(&FileRemovalStrategy, false, &files),
+                StrategyPlan::new(&BisectionStrategy, false, &files),
+            ],
+            baseline_time: Duration::from_millis(500),
+        };
+        assert!(plan.candidates() > 2);
+        assert_eq!(plan.estimate(), Duration::from_millis(500) * plan.candidates() as u32);
+
+        let rendered = plan.render(&files);
+        assert!(rendered.starts_with("=== Dry Run ===\n"));
+        assert!(rendered.contains(&format!("file_removal: {} candidates", plan.strategies[0].chomps.len())));
+        assert!(rendered.contains("Candidates per file:"));
+        assert!(rendered.contains(&format!("Total candidates: {}\n", plan.candidates())));
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("plan.tsv");
+        plan.export(&path, &files).unwrap();
+        let exported = fs::read_to_string(&path).unwrap();
+        assert_eq!(exported.lines().count(), plan.candidates() + 1);
+        assert!(exported.lines().skip(1).all(|line| line.split('\t').count() == 4));
+    }
+}
@@ -0,0 +1,145 @@
+use crate::command_runner::CommandRunner;
+use crate::file_manager::FileState;
+use anyhow::Result;
+use std::path::Path;
+use std::time::Duration;
+
+/// A cheap check a candidate must pass before the full command runs on it
+#[derive(Debug, Clone)]
+pub enum Precheck {
+    /// Shell command that must exit 0, e.g. `cargo check`
+    /// A `{file}` placeholder runs it once per changed file, e.g. `python -m py_compile {file}`
+    Command(String),
+    /// Built-in parser check: tree-sitter when built with the `tree-sitter` feature,
+    /// balanced brackets otherwise, plus matched `#if`/`#endif` in C/C++ files.
+    /// Only rejects files that parsed before the chomp.
+    Syntax,
+}
+
+impl Precheck {
+    pub fn parse(value: &str) -> Precheck {
+        match value.trim() {
+            "syntax" => Precheck::Syntax,
+            command => Precheck::Command(command.to_string()),
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        match self {
+            Precheck::Command(command) => format!("'{}'", command),
+            Precheck::Syntax => "built-in syntax check".to_string(),
+        }
+    }
+
+    /// Whether the chomped files pass this check
+    /// `before` holds the changed files as they were before the chomp, `after` as they are now.
+    /// Commands run in `working_dir`, like the main command.
+    pub fn passes(&self, before: &[FileState], after: &[&FileState], working_dir: Option<&Path>) -> Result<bool> {
+        let runner = |command: String| CommandRunner::new(command).with_working_dir(working_dir.map(Path::to_path_buf));
+        match self {
+            Precheck::Syntax => Ok(before.iter().zip(after).all(|(old, new)| {
+                new.deleted || !parses(old) || parses(new)
+            })),
+            Precheck::Command(command) if command.contains("{file}") => {
+                for state in after.iter().filter(|state| !state.deleted) {
+                    let path = shell_quote(&state.path.to_string_lossy());
+                    let result = runner(command.replace("{file}", &path)).run()?;
+                    if result.exit_code != 0 {
+                        return Ok(false);
+                    }
+                }
+                Ok(true)
+            }
+            Precheck::Command(command) => Ok(runner(command.clone()).run()?.exit_code == 0),
+        }
+    }
+}
+
+/// Counters for how the precheck filtered candidates
+#[derive(Debug, Clone, Default)]
+pub struct PrecheckStats {
+    pub checked: usize,
+    pub rejected: usize,
+    pub cache_hits: usize,
+    pub time: Duration,
+}
+
+impl PrecheckStats {
+    /// Estimated command time saved by not running rejected candidates
+    pub fn time_saved(&self, average_command: Duration) -> Duration {
+        (average_command * self.rejected as u32).saturating_sub(self.time)
+    }
+}
+
+fn parses(state: &FileState) -> bool {
+    let is_c = state.path.extension().is_some_and(|ext| ext == "c" || ext == "cpp" || ext == "h");
+    if is_c && !crate::strategies::conditionals_balanced(&state.current_content()) {
+        return false;
+    }
+    parses_syntax(state)
+}
+
+#[cfg(feature = "tree-sitter")]
+fn parses_syntax(state: &FileState) -> bool {
+    !crate::syntax::has_errors(&state.path, &state.current_content())
+}
+
+#[cfg(not(feature = "tree-sitter"))]
+fn parses_syntax(state: &FileState) -> bool {
+    let mut depth = 0;
+    for line in state.current_content().lines() {
+        depth += crate::block_tree::bracket_delta(line);
+        if depth < 0 {
+            return false;
+        }
+    }
+    depth == 0
+}
+
+pub fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_parse_precheck() {
+        assert!(matches!(Precheck::parse("syntax"), Precheck::Syntax));
+        assert!(matches!(Precheck::parse("cargo check"), Precheck::Command(c) if c == "cargo check"));
+    }
+
+    #[test]
+    fn test_syntax_precheck() {
+        let before = FileState::new(PathBuf::from("main.rs"), "fn main() {\n    f();\n}".to_string());
+        let mut after = before.clone();
+        after.blank_lines(&[1]);
+        assert!(Precheck::Syntax.passes(std::slice::from_ref(&before), &[&after], None).unwrap());
+
+        after.blank_lines(&[2]);
+        assert!(!Precheck::Syntax.passes(&[before], &[&after], None).unwrap());
+    }
+
+    #[test]
+    fn test_syntax_precheck_conditionals() {
+        let before = FileState::new(PathBuf::from("main.c"), "#ifdef X\nint x;\n#endif".to_string());
+        let mut after = before.clone();
+        after.blank_lines(&[0]);
+        assert!(!Precheck::Syntax.passes(&[before], &[&after], None).unwrap());
+    }
+
+    #[test]
+    fn test_command_precheck_per_file() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("it's.txt");
+        std::fs::write(&path, "ok").unwrap();
+        let state = FileState::new(path, "ok".to_string());
+
+        let check = Precheck::Command("grep -q ok {file}".to_string());
+        assert!(check.passes(std::slice::from_ref(&state), &[&state], None).unwrap());
+        let check = Precheck::Command("grep -q missing {file}".to_string());
+        assert!(!check.passes(std::slice::from_ref(&state), &[&state], None).unwrap());
+    }
+}
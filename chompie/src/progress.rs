@@ -1,10 +1,17 @@
-use std::time::Instant;
+use indicatif::{ProgressBar, ProgressStyle};
+use std::time::{Duration, Instant};
 
+/// Progress through one strategy's candidates
+/// With a bar, every update redraws a single line on stderr; indicatif hides it when stderr
+/// isn't a terminal.
 pub struct ProgressTracker {
     total_ranges: usize,
     chomps_performed: usize,
     successful_chomps: usize,
+    command_runs: usize,
+    command_time: Duration,
     start_time: Instant,
+    bar: Option<ProgressBar>,
 }
 
 impl ProgressTracker {
@@ -13,21 +20,75 @@ impl ProgressTracker {
             total_ranges,
             chomps_performed: 0,
             successful_chomps: 0,
+            command_runs: 0,
+            command_time: Duration::ZERO,
             start_time: Instant::now(),
+            bar: None,
         }
     }
 
+    /// Track progress with a bar labelled with the strategy's name
+    pub fn with_bar(total_ranges: usize, strategy: &str) -> Self {
+        let style = ProgressStyle::with_template("  {prefix} [{bar:30}] {pos}/{len} {wide_msg}")
+            .expect("the template is valid")
+            .progress_chars("=> ");
+        let bar = ProgressBar::new(total_ranges as u64).with_style(style).with_prefix(strategy.to_string());
+        let tracker = ProgressTracker { bar: Some(bar), ..ProgressTracker::new(total_ranges) };
+        if let Some(bar) = &tracker.bar {
+            bar.set_message(tracker.status());
+        }
+        tracker
+    }
+
     pub fn record_chomp(&mut self, success: bool) {
         self.chomps_performed += 1;
         if success {
             self.successful_chomps += 1;
         }
+        if let Some(bar) = &self.bar {
+            bar.set_position(self.chomps_performed as u64);
+            bar.set_message(self.status());
+        }
     }
 
-    pub fn display(&self) {
-        let elapsed = self.start_time.elapsed();
-        let elapsed_secs = elapsed.as_secs();
+    /// One run of the command, which most candidates need and some are spared
+    pub fn record_command(&mut self, duration: Duration) {
+        self.command_runs += 1;
+        self.command_time += duration;
+    }
+
+    /// Candidates tried per minute, skipped ones included
+    pub fn attempts_per_minute(&self) -> f64 {
+        let minutes = self.start_time.elapsed().as_secs_f64() / 60.0;
+        if minutes == 0.0 {
+            return 0.0;
+        }
+        self.chomps_performed as f64 / minutes
+    }
+
+    pub fn average_command_time(&self) -> Duration {
+        if self.command_runs == 0 {
+            return Duration::ZERO;
+        }
+        self.command_time / self.command_runs as u32
+    }
 
+    /// Kept chomps, throughput, command duration and ETA
+    pub fn status(&self) -> String {
+        let eta = match self.eta() {
+            Some(eta) => format_duration(eta),
+            None => "-".to_string(),
+        };
+        format!(
+            "kept {} | {:.1}/min | cmd {} | ETA {}",
+            self.successful_chomps,
+            self.attempts_per_minute(),
+            format_duration(self.average_command_time()),
+            eta
+        )
+    }
+
+    pub fn display(&self) {
         let percent = if self.total_ranges > 0 {
             (self.chomps_performed as f64 / self.total_ranges as f64) * 100.0
         } else {
@@ -35,27 +96,30 @@ impl ProgressTracker {
         };
 
         println!(
-            "Chomps: {}/{} ({:.1}%) | Successful: {} | Time: {}s",
-            self.chomps_performed, self.total_ranges, percent, self.successful_chomps, elapsed_secs
+            "Chomps: {}/{} ({:.1}%) | Successful: {} | {:.1} attempts/min | Avg command: {} | Time: {}s",
+            self.chomps_performed,
+            self.total_ranges,
+            percent,
+            self.successful_chomps,
+            self.attempts_per_minute(),
+            format_duration(self.average_command_time()),
+            self.start_time.elapsed().as_secs()
         );
     }
 
-    pub fn summary(&self, initial_lines: usize, final_lines: usize) {
-        let elapsed = self.start_time.elapsed();
-        let elapsed_secs = elapsed.as_secs();
-        let reduction_percent = if initial_lines > 0 {
-            ((initial_lines - final_lines) as f64 / initial_lines as f64) * 100.0
-        } else {
-            0.0
-        };
+    /// Print a line without tearing the bar
+    pub fn println(&self, line: &str) {
+        match &self.bar {
+            Some(bar) => bar.suspend(|| eprintln!("{}", line)),
+            None => eprintln!("{}", line),
+        }
+    }
 
-        println!("\n=== Chomping Complete ===");
-        println!("Initial lines: {}", initial_lines);
-        println!("Final lines: {}", final_lines);
-        println!("Reduction: {:.1}%", reduction_percent);
-        println!("Total chomps: {}", self.chomps_performed);
-        println!("Successful chomps: {}", self.successful_chomps);
-        println!("Time elapsed: {}s", elapsed_secs);
+    /// Remove the bar, e.g. before the strategy's summary line is printed
+    pub fn finish(&self) {
+        if let Some(bar) = &self.bar {
+            bar.finish_and_clear();
+        }
     }
 
     pub fn chomps_performed(&self) -> usize {
@@ -65,6 +129,37 @@ impl ProgressTracker {
     pub fn successful_chomps(&self) -> usize {
         self.successful_chomps
     }
+
+    pub fn total_ranges(&self) -> usize {
+        self.total_ranges
+    }
+
+    /// The strategy's candidates changed, e.g. regenerated after a kept chomp
+    pub fn set_total_ranges(&mut self, total_ranges: usize) {
+        self.total_ranges = total_ranges;
+        if let Some(bar) = &self.bar {
+            bar.set_length(total_ranges as u64);
+        }
+    }
+
+    /// Time left at the average pace so far, None until the first chomp
+    pub fn eta(&self) -> Option<Duration> {
+        if self.chomps_performed == 0 {
+            return None;
+        }
+        let remaining = self.total_ranges.saturating_sub(self.chomps_performed);
+        Some(self.start_time.elapsed() / self.chomps_performed as u32 * remaining as u32)
+    }
+}
+
+/// A duration for people: `4.2s`, `3m 12s`, `2h 5m`
+pub fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    match secs {
+        0..=59 => format!("{:.1}s", duration.as_secs_f64()),
+        60..=3599 => format!("{}m {}s", secs / 60, secs % 60),
+        _ => format!("{}h {}m", secs / 3600, secs % 3600 / 60),
+    }
 }
 
 #[cfg(test)]
@@ -86,4 +181,36 @@ mod tests {
         assert_eq!(tracker.chomps_performed(), 2);
         assert_eq!(tracker.successful_chomps(), 1);
     }
+
+    #[test]
+    fn test_command_time_and_status() {
+        let mut tracker = ProgressTracker::with_bar(10, "bisection");
+        assert_eq!(tracker.average_command_time(), Duration::ZERO);
+        tracker.record_command(Duration::from_secs(1));
+        tracker.record_command(Duration::from_secs(3));
+        tracker.record_chomp(true);
+        assert_eq!(tracker.average_command_time(), Duration::from_secs(2));
+        assert!(tracker.attempts_per_minute() > 0.0);
+        assert!(tracker.status().starts_with("kept 1 | "), "{}", tracker.status());
+        assert!(tracker.status().contains("| cmd 2.0s | ETA "), "{}", tracker.status());
+        tracker.finish();
+    }
+
+    #[test]
+    fn test_eta() {
+        let mut tracker = ProgressTracker::new(4);
+        assert_eq!(tracker.eta(), None);
+        tracker.record_chomp(true);
+        tracker.record_chomp(true);
+        tracker.record_chomp(false);
+        tracker.record_chomp(false);
+        assert_eq!(tracker.eta(), Some(Duration::ZERO));
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(Duration::from_millis(4200)), "4.2s");
+        assert_eq!(format_duration(Duration::from_secs(192)), "3m 12s");
+        assert_eq!(format_duration(Duration::from_secs(7500)), "2h 5m");
+    }
 }
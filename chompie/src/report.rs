@@ -0,0 +1,267 @@
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+/// How the precheck filtered candidates, for the report
+#[derive(Debug, Clone, PartialEq)]
+pub struct PrecheckSummary {
+    pub checked: usize,
+    pub rejected: usize,
+    pub cache_hits: usize,
+    pub time_secs: f64,
+    pub saved_secs: f64,
+}
+
+/// The summary printed at the end of a run, saved with its session so `chompie report` can
+/// show it again
+#[derive(Debug, Clone, PartialEq)]
+pub struct Report {
+    pub command: String,
+    pub file_count: usize,
+    pub files_removed: usize,
+    pub initial_lines: usize,
+    pub final_lines: usize,
+    pub initial_chars: usize,
+    pub final_chars: usize,
+    pub successful_chomps: usize,
+    pub chomps_tested: usize,
+    /// Runs of the full command, baseline included; more than the states tested when a
+    /// chomp's state had to be run again
+    pub command_runs: usize,
+    pub rounds: usize,
+    pub elapsed_secs: f64,
+    pub average_command_secs: f64,
+    pub precheck: Option<PrecheckSummary>,
+    /// Branch and commit count of `--git-commits`
+    pub history: Option<(String, usize)>,
+}
+
+impl Report {
+    pub fn reduction_percent(&self) -> f64 {
+        if self.initial_lines == 0 {
+            return 0.0;
+        }
+        (self.initial_lines - self.final_lines) as f64 / self.initial_lines as f64 * 100.0
+    }
+
+    pub fn render(&self) -> String {
+        let mut out = String::from("=== Final Results ===\n");
+        out.push_str(&format!("Initial lines: {}\n", self.initial_lines));
+        out.push_str(&format!("Final lines: {}\n", self.final_lines));
+        out.push_str(&format!("Files removed: {}/{}\n", self.files_removed, self.file_count));
+        out.push_str(&format!("Characters: {} -> {}\n", self.initial_chars, self.final_chars));
+        out.push_str(&format!("Reduction: {:.1}%\n", self.reduction_percent()));
+        out.push_str(&format!("Total successful chomps: {}\n", self.successful_chomps));
+        out.push_str(&format!("Total chomps tested: {}\n", self.chomps_tested));
+        out.push_str(&format!("Rounds: {}\n", self.rounds));
+        out.push_str(&format!("Time elapsed: {:.1}s\n", self.elapsed_secs));
+        out.push_str(&format!("Average command time: {:.2}s\n", self.average_command_secs));
+        if self.elapsed_secs > 0.0 && self.command_runs > 0 {
+            let per_minute = self.command_runs as f64 * 60.0 / self.elapsed_secs;
+            out.push_str(&format!("Command runs per minute: {:.1}\n", per_minute));
+        }
+
+        if let Some(precheck) = &self.precheck {
+            out.push_str("\n=== Precheck ===\n");
+            out.push_str(&format!("Candidates checked: {}\n", precheck.checked));
+            out.push_str(&format!("Rejected before running the command: {}\n", precheck.rejected));
+            out.push_str(&format!("Cached verdicts reused: {}\n", precheck.cache_hits));
+            out.push_str(&format!("Precheck time: {:.1}s\n", precheck.time_secs));
+            out.push_str(&format!("Estimated time saved: {:.1}s\n", precheck.saved_secs));
+        }
+
+        if let Some((branch, commits)) = &self.history {
+            out.push_str("\n=== History ===\n");
+            out.push_str(&format!("Branch: {} ({} commits)\n", branch, commits));
+            out.push_str(&format!("Review with: git log --stat {}\n", branch));
+        }
+        out
+    }
+
+    /// This leg's report continued from `previous`, an earlier leg of the same resumed session
+    /// Counts of command runs and time add up; the line counts are already the session's.
+    pub fn after(mut self, previous: &Report) -> Report {
+        let runs = self.command_runs + previous.command_runs;
+        if runs > 0 {
+            self.average_command_secs = (self.average_command_secs * self.command_runs as f64
+                + previous.average_command_secs * previous.command_runs as f64)
+                / runs as f64;
+        }
+        self.command_runs = runs;
+        self.chomps_tested += previous.chomps_tested;
+        self.rounds += previous.rounds;
+        self.elapsed_secs += previous.elapsed_secs;
+        self.precheck = match (self.precheck.take(), &previous.precheck) {
+            (Some(this), Some(earlier)) => Some(PrecheckSummary {
+                checked: this.checked + earlier.checked,
+                rejected: this.rejected + earlier.rejected,
+                cache_hits: this.cache_hits + earlier.cache_hits,
+                time_secs: this.time_secs + earlier.time_secs,
+                saved_secs: this.saved_secs + earlier.saved_secs,
+            }),
+            (this, earlier) => this.or_else(|| earlier.clone()),
+        };
+        if self.history.is_none() {
+            self.history = previous.history.clone();
+        }
+        self
+    }
+
+    /// Save as `key: value` lines
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let mut fields = vec![
+            ("command", self.command.clone()),
+            ("file_count", self.file_count.to_string()),
+            ("files_removed", self.files_removed.to_string()),
+            ("initial_lines", self.initial_lines.to_string()),
+            ("final_lines", self.final_lines.to_string()),
+            ("initial_chars", self.initial_chars.to_string()),
+            ("final_chars", self.final_chars.to_string()),
+            ("successful_chomps", self.successful_chomps.to_string()),
+            ("chomps_tested", self.chomps_tested.to_string()),
+            ("command_runs", self.command_runs.to_string()),
+            ("rounds", self.rounds.to_string()),
+            ("elapsed_secs", self.elapsed_secs.to_string()),
+            ("average_command_secs", self.average_command_secs.to_string()),
+        ];
+        if let Some(precheck) = &self.precheck {
+            fields.extend([
+                ("precheck_checked", precheck.checked.to_string()),
+                ("precheck_rejected", precheck.rejected.to_string()),
+                ("precheck_cache_hits", precheck.cache_hits.to_string()),
+                ("precheck_time_secs", precheck.time_secs.to_string()),
+                ("precheck_saved_secs", precheck.saved_secs.to_string()),
+            ]);
+        }
+        if let Some((branch, commits)) = &self.history {
+            fields.extend([("history_branch", branch.clone()), ("history_commits", commits.to_string())]);
+        }
+
+        let content: String = fields.iter().map(|(key, value)| format!("{}: {}\n", key, value)).collect();
+        fs::write(path, content).with_context(|| format!("Failed to save the report to {:?}", path))
+    }
+
+    pub fn load(path: &Path) -> Result<Report> {
+        let content = fs::read_to_string(path).with_context(|| format!("No report at {:?}", path))?;
+        let fields: HashMap<&str, &str> = content.lines().filter_map(|line| line.split_once(": ")).collect();
+        let text = |key: &str| fields.get(key).map(|value| value.to_string());
+        let number = |key: &str| -> Result<usize> {
+            fields.get(key).with_context(|| format!("The report has no {}", key))?.parse().context(key.to_string())
+        };
+
+        let mut precheck = None;
+        if fields.contains_key("precheck_checked") {
+            precheck = Some(PrecheckSummary {
+                checked: number("precheck_checked")?,
+                rejected: number("precheck_rejected")?,
+                cache_hits: number("precheck_cache_hits")?,
+                time_secs: text("precheck_time_secs").unwrap_or_default().parse()?,
+                saved_secs: text("precheck_saved_secs").unwrap_or_default().parse()?,
+            });
+        }
+        let history = match text("history_branch") {
+            Some(branch) => Some((branch, number("history_commits")?)),
+            None => None,
+        };
+
+        let chomps_tested = number("chomps_tested")?;
+        Ok(Report {
+            command: text("command").unwrap_or_default(),
+            file_count: number("file_count")?,
+            files_removed: number("files_removed")?,
+            initial_lines: number("initial_lines")?,
+            final_lines: number("final_lines")?,
+            initial_chars: number("initial_chars")?,
+            final_chars: number("final_chars")?,
+            successful_chomps: number("successful_chomps")?,
+            chomps_tested,
+            // Missing from reports saved before they were recorded, like whole seconds elapsed
+            command_runs: number("command_runs").unwrap_or(chomps_tested),
+            rounds: number("rounds")?,
+            elapsed_secs: text("elapsed_secs").unwrap_or_default().parse().context("elapsed_secs")?,
+            average_command_secs: text("average_command_secs").unwrap_or_default().parse().unwrap_or(0.0),
+            precheck,
+            history,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report() -> Report {
+        Report {
+            command: "cargo test".to_string(),
+            file_count: 4,
+            files_removed: 1,
+            initial_lines: 200,
+            final_lines: 50,
+            initial_chars: 4000,
+            final_chars: 900,
+            successful_chomps: 12,
+            chomps_tested: 80,
+            command_runs: 84,
+            rounds: 3,
+            elapsed_secs: 42.5,
+            average_command_secs: 0.25,
+            precheck: None,
+            history: Some(("chompie/20240229-235959".to_string(), 12)),
+        }
+    }
+
+    #[test]
+    fn test_render() {
+        let rendered = report().render();
+        assert!(rendered.starts_with("=== Final Results ===\nInitial lines: 200\n"));
+        assert!(rendered.contains("Reduction: 75.0%\n"));
+        assert!(rendered.contains("Time elapsed: 42.5s\nAverage command time: 0.25s\nCommand runs per minute: 118.6\n"));
+        assert!(rendered.contains("Branch: chompie/20240229-235959 (12 commits)\n"));
+        assert!(!rendered.contains("Precheck"));
+
+        // Runs under a second still get a rate, from the runs rather than the states tested
+        let quick = Report { chomps_tested: 10, command_runs: 14, elapsed_secs: 1.6, ..report() };
+        assert!(quick.render().contains("Time elapsed: 1.6s\nAverage command time: 0.25s\nCommand runs per minute: 525.0\n"));
+    }
+
+    #[test]
+    fn test_after() {
+        let earlier = report();
+        let mut later = report();
+        later.chomps_tested = 20;
+        later.command_runs = 21;
+        later.average_command_secs = 1.25;
+        later.history = None;
+        later.precheck = Some(PrecheckSummary { checked: 9, rejected: 4, cache_hits: 2, time_secs: 1.5, saved_secs: 7.25 });
+
+        let merged = later.after(&earlier);
+        assert_eq!((merged.chomps_tested, merged.command_runs, merged.rounds), (100, 105, 6));
+        assert_eq!(merged.elapsed_secs, 85.0);
+        assert!((merged.average_command_secs - 0.45).abs() < 1e-9);
+        assert_eq!(merged.precheck.as_ref().unwrap().checked, 9);
+        assert_eq!(merged.history, earlier.history);
+        // Line counts are the session's already
+        assert_eq!((merged.initial_lines, merged.final_lines), (200, 50));
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("report");
+        let mut report = report();
+        report.save(&path).unwrap();
+        assert_eq!(Report::load(&path).unwrap(), report);
+
+        report.precheck = Some(PrecheckSummary { checked: 9, rejected: 4, cache_hits: 2, time_secs: 1.5, saved_secs: 7.25 });
+        report.history = None;
+        report.save(&path).unwrap();
+        assert_eq!(Report::load(&path).unwrap(), report);
+
+        // Older reports had whole seconds and no count of command runs
+        let old = std::fs::read_to_string(&path).unwrap();
+        std::fs::write(&path, old.replace("elapsed_secs: 42.5", "elapsed_secs: 42").replace("command_runs: 84\n", "")).unwrap();
+        let loaded = Report::load(&path).unwrap();
+        assert_eq!((loaded.elapsed_secs, loaded.command_runs), (42.0, 80));
+    }
+}
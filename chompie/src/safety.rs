@@ -0,0 +1,146 @@
+use crate::file_manager::FileManager;
+use crate::git;
+use anyhow::Result;
+use std::path::{Path, PathBuf};
+
+/// Checks a run must pass before chompie touches anything
+///
+/// Every run refuses to chomp `/`, the home directory, or more than `max_files` files, which
+/// almost always means `--directory` points somewhere unintended. Runs that modify files in
+/// place additionally refuse files that git couldn't restore: outside a repository, or with
+/// uncommitted changes.
+#[derive(Debug, Clone)]
+pub struct Guardrails {
+    pub max_files: usize,
+    pub allow_dirty: bool,
+    pub allow_no_vcs: bool,
+}
+
+impl Guardrails {
+    /// Refuse directories no one means to chomp
+    pub fn check_directory(&self, directory: &Path) -> Result<()> {
+        let resolved = directory.canonicalize()?;
+        if resolved.parent().is_none() {
+            anyhow::bail!("Refusing to chomp the root directory {:?}", resolved);
+        }
+        if home_dir().and_then(|home| home.canonicalize().ok()).is_some_and(|home| home == resolved) {
+            anyhow::bail!("Refusing to chomp the home directory {:?}", resolved);
+        }
+        Ok(())
+    }
+
+    pub fn check_file_count(&self, count: usize) -> Result<()> {
+        if count > self.max_files {
+            anyhow::bail!(
+                "Refusing to chomp {} files, more than --max-files {}; point --directory at a smaller tree or raise the limit",
+                count,
+                self.max_files
+            );
+        }
+        Ok(())
+    }
+
+    /// Refuse to modify files in place that version control can't bring back
+    pub fn check_in_place(&self, directory: &Path, file_manager: &FileManager) -> Result<()> {
+        let Some(uncommitted) = git::uncommitted_files(directory)? else {
+            if self.allow_no_vcs {
+                return Ok(());
+            }
+            anyhow::bail!(
+                "{:?} isn't under version control, so chomped files couldn't be restored; pass --allow-no-vcs to chomp it anyway",
+                directory
+            );
+        };
+        if self.allow_dirty {
+            return Ok(());
+        }
+
+        let dirty: Vec<&PathBuf> = files_to_touch(file_manager)
+            .into_iter()
+            .map(|(path, _)| path)
+            .filter(|path| git::absolute(path).is_ok_and(|path| uncommitted.contains(&path)))
+            .collect();
+        if !dirty.is_empty() {
+            let list: Vec<String> = dirty.iter().map(|path| format!("  {}", path.display())).collect();
+            anyhow::bail!(
+                "These files have uncommitted changes that chomping in place would destroy:\n{}\nCommit or stash them, or pass --allow-dirty",
+                list.join("\n")
+            );
+        }
+        Ok(())
+    }
+}
+
+/// The files a chomp may modify with their non-blank line counts, sorted by path
+pub fn files_to_touch(file_manager: &FileManager) -> Vec<(&PathBuf, usize)> {
+    let mut files: Vec<(&PathBuf, usize)> = file_manager
+        .files()
+        .iter()
+        .map(|(path, state)| (path, state.non_blank_lines()))
+        .collect();
+    files.sort();
+    files
+}
+
+fn home_dir() -> Option<PathBuf> {
+    std::env::var_os("HOME")
+        .or_else(|| std::env::var_os("USERPROFILE"))
+        .map(PathBuf::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::process::Command;
+    use tempfile::TempDir;
+
+    fn guardrails() -> Guardrails {
+        Guardrails { max_files: 2, allow_dirty: false, allow_no_vcs: false }
+    }
+
+    #[test]
+    fn test_check_directory() {
+        let temp_dir = TempDir::new().unwrap();
+        assert!(guardrails().check_directory(temp_dir.path()).is_ok());
+        assert!(guardrails().check_directory(Path::new("/")).is_err());
+        assert!(guardrails().check_directory(&temp_dir.path().join("..").join("..").join("..").join("..")).is_err());
+        if let Some(home) = home_dir().filter(|home| home.is_dir()) {
+            assert!(guardrails().check_directory(&home).is_err());
+        }
+    }
+
+    #[test]
+    fn test_check_file_count() {
+        assert!(guardrails().check_file_count(2).is_ok());
+        assert!(guardrails().check_file_count(3).is_err());
+    }
+
+    #[test]
+    fn test_check_in_place() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path();
+        std::fs::write(dir.join("a.py"), "x = 1\n").unwrap();
+        let mut file_manager = FileManager::new();
+        file_manager.add_directory(dir).unwrap();
+        assert_eq!(files_to_touch(&file_manager), vec![(&dir.join("a.py"), 1)]);
+
+        // Not under version control
+        assert!(guardrails().check_in_place(dir, &file_manager).is_err());
+        let allow_no_vcs = Guardrails { allow_no_vcs: true, ..guardrails() };
+        assert!(allow_no_vcs.check_in_place(dir, &file_manager).is_ok());
+
+        // Untracked, then committed
+        let git = |args: &[&str]| {
+            assert!(Command::new("git").arg("-C").arg(dir).args(args).status().unwrap().success());
+        };
+        git(&["init", "-q"]);
+        let error = guardrails().check_in_place(dir, &file_manager).unwrap_err().to_string();
+        assert!(error.contains("a.py"), "{}", error);
+        let allow_dirty = Guardrails { allow_dirty: true, ..guardrails() };
+        assert!(allow_dirty.check_in_place(dir, &file_manager).is_ok());
+
+        git(&["add", "."]);
+        git(&["-c", "user.name=t", "-c", "user.email=t@t", "commit", "-q", "-m", "init"]);
+        assert!(guardrails().check_in_place(dir, &file_manager).is_ok());
+    }
+}
@@ -0,0 +1,151 @@
+use crate::backup::BACKUP_DIR;
+use crate::git::{absolute, Worktree};
+use crate::timestamp;
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// A throwaway copy of a directory to chomp without touching the original
+pub enum Sandbox {
+    /// A git worktree of the repository the directory is in
+    Worktree(Worktree),
+    /// A plain copy of the directory, outside git
+    Copy { directory: PathBuf, path: PathBuf },
+}
+
+impl Sandbox {
+    /// Copy `directory` into the temp directory, as a git worktree if it's in a repository
+    pub fn create(directory: &Path) -> Result<Sandbox> {
+        if let Some(worktree) = Worktree::create(directory)? {
+            return Ok(Sandbox::Worktree(worktree));
+        }
+
+        let directory = directory.canonicalize()?;
+        // Hidden, so scanning a directory that holds the temp directory passes over it
+        let path = std::env::temp_dir().join(format!(".chompie-sandbox-{}-{}", timestamp::now()?, std::process::id()));
+        fs::create_dir_all(&path)?;
+        let sandbox = Sandbox::Copy { directory, path: path.canonicalize()? };
+        if let Sandbox::Copy { directory, path } = &sandbox {
+            if let Err(e) = copy_directory(directory, path, directory, path) {
+                sandbox.remove()?;
+                return Err(e.context("Failed to copy the directory into a sandbox"));
+            }
+        }
+        Ok(sandbox)
+    }
+
+    pub fn path(&self) -> &Path {
+        match self {
+            Sandbox::Worktree(worktree) => worktree.path(),
+            Sandbox::Copy { path, .. } => path,
+        }
+    }
+
+    /// Where a path in the original tree lives in the sandbox, None if it's outside it
+    pub fn map(&self, path: &Path) -> Option<PathBuf> {
+        match self {
+            Sandbox::Worktree(worktree) => worktree.map(path),
+            Sandbox::Copy { directory, path: sandbox } => {
+                let absolute = absolute(path).ok()?;
+                let relative = absolute.strip_prefix(directory).ok()?;
+                Some(sandbox.join(relative))
+            }
+        }
+    }
+
+    /// Remove the sandbox and its files
+    pub fn remove(&self) -> Result<()> {
+        match self {
+            Sandbox::Worktree(worktree) => worktree.remove(),
+            Sandbox::Copy { path, .. } => {
+                fs::remove_dir_all(path).with_context(|| format!("Failed to remove the sandbox {:?}", path))
+            }
+        }
+    }
+}
+
+/// Copy everything under `from` to `to`, except version control, chompie's own backups and
+/// the sandbox itself, which is inside `from` when the temp directory is
+fn copy_directory(from: &Path, to: &Path, root: &Path, sandbox: &Path) -> Result<()> {
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        let source = entry.path();
+        if entry.file_name() == ".git" || source == root.join(BACKUP_DIR) || source == sandbox {
+            continue;
+        }
+
+        let target = to.join(entry.file_name());
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            fs::create_dir_all(&target)?;
+            copy_directory(&source, &target, root, sandbox)?;
+        } else if file_type.is_file() {
+            fs::copy(&source, &target).with_context(|| format!("Failed to copy {:?}", source))?;
+        } else if file_type.is_symlink() {
+            copy_symlink(&source, &target)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+fn copy_symlink(source: &Path, target: &Path) -> Result<()> {
+    std::os::unix::fs::symlink(fs::read_link(source)?, target).with_context(|| format!("Failed to copy {:?}", source))
+}
+
+#[cfg(not(unix))]
+fn copy_symlink(source: &Path, target: &Path) -> Result<()> {
+    if source.is_file() {
+        fs::copy(source, target).with_context(|| format!("Failed to copy {:?}", source))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_copy_sandbox() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().join("project");
+        fs::create_dir_all(root.join("src")).unwrap();
+        fs::create_dir_all(root.join(BACKUP_DIR)).unwrap();
+        fs::write(root.join("src/a.py"), "print(1)\n").unwrap();
+        fs::write(root.join("data.txt"), "input\n").unwrap();
+        fs::write(root.join(BACKUP_DIR).join("session"), "old\n").unwrap();
+
+        let sandbox = Sandbox::create(&root).unwrap();
+        assert!(matches!(sandbox, Sandbox::Copy { .. }));
+        let copied = sandbox.map(&root.join("src/a.py")).unwrap();
+        assert!(copied.starts_with(sandbox.path()));
+        assert_eq!(fs::read_to_string(&copied).unwrap(), "print(1)\n");
+        assert!(sandbox.path().join("data.txt").is_file());
+        assert!(!sandbox.path().join(BACKUP_DIR).exists());
+        assert_eq!(sandbox.map(temp_dir.path()), None);
+
+        // Changes stay in the sandbox
+        fs::write(&copied, "").unwrap();
+        assert_eq!(fs::read_to_string(root.join("src/a.py")).unwrap(), "print(1)\n");
+
+        let path = sandbox.path().to_path_buf();
+        sandbox.remove().unwrap();
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_copy_skips_the_sandbox() {
+        // Analyzing a directory that holds the temp directory puts the sandbox inside it
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        let inside = root.join("tmp/.chompie-sandbox");
+        fs::create_dir_all(&inside).unwrap();
+        fs::write(root.join("a.py"), "print(1)\n").unwrap();
+
+        copy_directory(root, &inside, root, &inside).unwrap();
+        assert!(inside.join("a.py").is_file());
+        assert!(inside.join("tmp").is_dir());
+        assert!(!inside.join("tmp/.chompie-sandbox").exists());
+    }
+}
@@ -0,0 +1,175 @@
+use crate::block_tree::Block;
+use crate::coverage::Coverage;
+use crate::file_manager::FileState;
+use crate::strategy::{Chomp, ChompRange, Strategy};
+use std::cell::OnceCell;
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+/// Coverage strategy: uses an lcov tracefile from the baseline run as a removal oracle
+///
+/// Blocks the command never executed are proposed first, all of them at once and then in
+/// halves, quarters, ... down to single blocks. Executed lines come last, least-hit first.
+/// The tracefile is read on first use, i.e. right after the baseline run wrote it and the run
+/// checked it, and stays valid because chomping never moves lines.
+pub struct CoverageStrategy {
+    lcov: PathBuf,
+    coverage: OnceCell<Coverage>,
+}
+
+impl CoverageStrategy {
+    pub fn new(lcov: PathBuf) -> Self {
+        CoverageStrategy { lcov, coverage: OnceCell::new() }
+    }
+
+    #[cfg(test)]
+    fn with_coverage(coverage: Coverage) -> Self {
+        CoverageStrategy { lcov: PathBuf::new(), coverage: OnceCell::from(coverage) }
+    }
+
+    fn coverage(&self) -> &Coverage {
+        // The run checked the file after the baseline, so this only falls back if it vanished since
+        self.coverage.get_or_init(|| Coverage::load(&self.lcov).unwrap_or_default())
+    }
+}
+
+/// Lines [start, end) not covered by any child block, i.e. the header and closing lines
+fn own_lines(block: &Block) -> impl Iterator<Item = usize> + '_ {
+    (block.start_line..block.end_line)
+        .filter(|line| !block.children.iter().any(|child| (child.start_line..child.end_line).contains(line)))
+}
+
+/// Whether a block was never executed: it has coverage data, all of it zero, apart from a
+/// header that only ran to decide the block itself (`if x {`, `def f():`)
+/// Returns None for blocks without any coverage data, like comments or lone braces.
+fn is_cold(block: &Block, hits: &HashMap<usize, u64>) -> Option<bool> {
+    let own: Vec<u64> = own_lines(block).filter_map(|line| hits.get(&line).copied()).collect();
+    if block.children.is_empty() {
+        return (!own.is_empty()).then(|| own.iter().all(|&h| h == 0));
+    }
+
+    let children: Vec<bool> = block.children.iter().filter_map(|child| is_cold(child, hits)).collect();
+    if children.is_empty() {
+        return (!own.is_empty()).then(|| own.iter().all(|&h| h == 0));
+    }
+    let body_hits = own_lines(block)
+        .filter(|&line| line != block.start_line)
+        .any(|line| hits.get(&line).is_some_and(|&h| h > 0));
+    Some(children.iter().all(|&cold| cold) && !body_hits)
+}
+
+/// Outermost never-executed blocks
+fn collect_cold(blocks: &[Block], hits: &HashMap<usize, u64>, cold: &mut Vec<(usize, usize)>) {
+    for block in blocks {
+        match is_cold(block, hits) {
+            Some(true) => cold.push((block.start_line, block.end_line)),
+            Some(false) => collect_cold(&block.children, hits, cold),
+            None => {}
+        }
+    }
+}
+
+fn compound(ranges: &[ChompRange]) -> Chomp {
+    match ranges {
+        [range] => Chomp::Range(range.clone()),
+        _ => Chomp::Compound(ranges.iter().cloned().map(Chomp::Range).collect()),
+    }
+}
+
+impl Strategy for CoverageStrategy {
+    fn name(&self) -> &str {
+        "coverage"
+    }
+
+    fn generate_ranges(&self, _files: &HashMap<PathBuf, FileState>) -> Vec<ChompRange> {
+        // Batches span several ranges, see generate_chomps
+        Vec::new()
+    }
+
+    fn generate_chomps(&self, files: &HashMap<PathBuf, FileState>) -> Vec<Chomp> {
+        let coverage = self.coverage();
+        if coverage.is_empty() {
+            return Vec::new();
+        }
+
+        let mut paths: Vec<&PathBuf> = files.keys().filter(|path| !files[*path].deleted).collect();
+        paths.sort();
+
+        let mut cold = Vec::new();
+        let mut executed = Vec::new();
+        for path in paths {
+            let state = &files[path];
+            let Some(hits) = coverage.for_file(path) else {
+                continue;
+            };
+            let mut blocks = Vec::new();
+            collect_cold(&Block::from_file(state).children, hits, &mut blocks);
+            cold.extend(blocks.into_iter().map(|(start_line, end_line)| ChompRange {
+                file: path.clone(),
+                start_line,
+                end_line,
+            }));
+            for line in state.non_blank_line_indices() {
+                if let Some(&count) = hits.get(&line).filter(|&&count| count > 0) {
+                    executed.push((count, path.clone(), line));
+                }
+            }
+        }
+
+        let mut chomps = Vec::new();
+        if !cold.is_empty() {
+            // Everything cold at once, then halves, quarters, ... down to single blocks
+            let mut chunk_size = cold.len();
+            loop {
+                chomps.extend(cold.chunks(chunk_size).map(compound));
+                if chunk_size == 1 {
+                    break;
+                }
+                chunk_size = chunk_size.div_ceil(2);
+            }
+        }
+
+        executed.sort_by(|a, b| a.0.cmp(&b.0).then_with(|| (&a.1, a.2).cmp(&(&b.1, b.2))));
+        chomps.extend(executed.into_iter().map(|(_, file, line)| {
+            Chomp::Range(ChompRange { file, start_line: line, end_line: line + 1 })
+        }));
+        chomps
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_coverage_strategy() {
+        let content = "fn main() {\n    if debug() {\n        log();\n    }\n    run();\n}\n\nfn unused() {\n    x();\n}";
+        let lcov = "SF:src/main.rs\nDA:1,1\nDA:2,1\nDA:3,0\nDA:5,4\nDA:8,0\nDA:9,0\nend_of_record\n";
+        let path = PathBuf::from("src/main.rs");
+        let mut files = HashMap::new();
+        files.insert(path.clone(), FileState::new(path, content.to_string()));
+
+        let strategy = CoverageStrategy::with_coverage(Coverage::parse_lcov(lcov));
+        assert_eq!(strategy.name(), "coverage");
+        let described: Vec<String> = strategy.generate_chomps(&files).iter().map(|c| c.to_string()).collect();
+        assert_eq!(
+            described,
+            vec![
+                // Both cold blocks together, then one at a time
+                "[\"src/main.rs\" lines 1-4 (3 lines); \"src/main.rs\" lines 7-10 (3 lines)]",
+                "\"src/main.rs\" lines 1-4 (3 lines)",
+                "\"src/main.rs\" lines 7-10 (3 lines)",
+                // Executed lines last, least hit first
+                "\"src/main.rs\" lines 0-1 (1 lines)",
+                "\"src/main.rs\" lines 1-2 (1 lines)",
+                "\"src/main.rs\" lines 4-5 (1 lines)",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_missing_lcov_generates_nothing() {
+        let strategy = CoverageStrategy::new(PathBuf::from("/nonexistent/lcov.info"));
+        assert!(strategy.generate_chomps(&HashMap::new()).is_empty());
+    }
+}
@@ -0,0 +1,144 @@
+use crate::file_manager::FileState;
+use crate::strategy::{Chomp, ChompRange, Strategy};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+/// File removal strategy: tries removing whole files before any line-level work
+///
+/// Files are tried largest first, each one deleted from disk and then blanked completely
+/// (an empty file still satisfies e.g. a Rust `mod` declaration). After single files,
+/// it tries deleting pairs, then groups of four, and so on in ddmin style, which catches
+/// files that can only go together.
+pub struct FileRemovalStrategy;
+
+impl FileRemovalStrategy {
+    /// Files that still have content, largest first
+    fn live_files(files: &HashMap<PathBuf, FileState>) -> Vec<&FileState> {
+        let mut live: Vec<&FileState> = files
+            .values()
+            .filter(|state| state.non_blank_lines() > 0)
+            .collect();
+        live.sort_by(|a, b| {
+            b.non_blank_lines()
+                .cmp(&a.non_blank_lines())
+                .then_with(|| a.path.cmp(&b.path))
+        });
+        live
+    }
+}
+
+impl Strategy for FileRemovalStrategy {
+    fn name(&self) -> &str {
+        "file_removal"
+    }
+
+    fn generate_ranges(&self, files: &HashMap<PathBuf, FileState>) -> Vec<ChompRange> {
+        // Blank each file completely
+        Self::live_files(files)
+            .into_iter()
+            .map(|state| ChompRange {
+                file: state.path.clone(),
+                start_line: 0,
+                end_line: state.total_lines(),
+            })
+            .collect()
+    }
+
+    fn generate_chomps(&self, files: &HashMap<PathBuf, FileState>) -> Vec<Chomp> {
+        let live = Self::live_files(files);
+        let mut chomps = Vec::new();
+
+        // Single files: delete, then fall back to blanking
+        for (state, range) in live.iter().zip(self.generate_ranges(files)) {
+            chomps.push(Chomp::DeleteFiles(vec![state.path.clone()]));
+            chomps.push(Chomp::Range(range));
+        }
+
+        // Pairs, then groups of increasing size
+        let mut group_size = 2;
+        while group_size < live.len() {
+            // Leftover single files were already tried above
+            for group in live.chunks(group_size).filter(|group| group.len() > 1) {
+                chomps.push(Chomp::DeleteFiles(
+                    group.iter().map(|state| state.path.clone()).collect(),
+                ));
+            }
+            group_size *= 2;
+        }
+
+        chomps
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(files: &mut HashMap<PathBuf, FileState>, name: &str, lines: usize) -> PathBuf {
+        let path = PathBuf::from(name);
+        let content = (0..lines).map(|i| format!("line{}", i)).collect::<Vec<_>>().join("\n");
+        files.insert(path.clone(), FileState::new(path.clone(), content));
+        path
+    }
+
+    #[test]
+    fn test_file_removal_largest_first() {
+        let mut files = HashMap::new();
+        let small = file(&mut files, "small.rs", 2);
+        let large = file(&mut files, "large.rs", 10);
+
+        let strategy = FileRemovalStrategy;
+        let chomps = strategy.generate_chomps(&files);
+        assert_eq!(strategy.name(), "file_removal");
+
+        // 2 files: delete + blank for each, no groups
+        assert_eq!(chomps.len(), 4);
+        match &chomps[0] {
+            Chomp::DeleteFiles(paths) => assert_eq!(paths, &vec![large.clone()]),
+            other => panic!("unexpected chomp: {}", other),
+        }
+        match &chomps[1] {
+            Chomp::Range(range) => {
+                assert_eq!(range.file, large);
+                assert_eq!((range.start_line, range.end_line), (0, 10));
+            }
+            other => panic!("unexpected chomp: {}", other),
+        }
+        match &chomps[2] {
+            Chomp::DeleteFiles(paths) => assert_eq!(paths, &vec![small]),
+            other => panic!("unexpected chomp: {}", other),
+        }
+    }
+
+    #[test]
+    fn test_file_removal_groups() {
+        let mut files = HashMap::new();
+        for i in 0..5 {
+            file(&mut files, &format!("f{}.rs", i), i + 1);
+        }
+
+        let chomps = FileRemovalStrategy.generate_chomps(&files);
+
+        // 5 singles * 2, then 2 pairs, then 1 group of four
+        assert_eq!(chomps.len(), 10 + 2 + 1);
+        match &chomps[10] {
+            Chomp::DeleteFiles(paths) => assert_eq!(paths.len(), 2),
+            other => panic!("unexpected chomp: {}", other),
+        }
+        match &chomps[12] {
+            Chomp::DeleteFiles(paths) => assert_eq!(paths.len(), 4),
+            other => panic!("unexpected chomp: {}", other),
+        }
+    }
+
+    #[test]
+    fn test_file_removal_skips_deleted() {
+        let mut files = HashMap::new();
+        let gone = file(&mut files, "gone.rs", 3);
+        file(&mut files, "kept.rs", 3);
+        files.get_mut(&gone).unwrap().delete();
+
+        let chomps = FileRemovalStrategy.generate_chomps(&files);
+        assert_eq!(chomps.len(), 2);
+    }
+}
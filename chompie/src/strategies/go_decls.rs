@@ -0,0 +1,247 @@
+use crate::block_tree::bracket_delta;
+use crate::file_manager::FileState;
+use crate::strategy::{Chomp, ChompRange, Strategy};
+use crate::tokens::{tokenize, TokenKind};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+/// Go declarations strategy: removes whole files and top-level declarations, pruning the
+/// imports each removal leaves unused
+///
+/// Go won't compile with an unused import, so blanking a function that was the last user of
+/// `fmt` fails no matter what. Each candidate here removes a `func`, `type`, `var` or `const`
+/// declaration (with its doc comment) and, in the same chomp, every import that no longer
+/// has a use in that file.
+pub struct GoDeclarationsStrategy;
+
+/// An import spec, e.g. `"fmt"` or `str "strings"`
+#[derive(Debug, PartialEq, Eq)]
+struct Import {
+    line: usize,
+    /// Name the package is referred to by in code
+    name: String,
+    /// Lines of the `import (` and `)` around it, if it's in a block
+    block: Option<(usize, usize)>,
+}
+
+/// Name code uses for an import spec like `"math/rand"` or `yaml "gopkg.in/yaml.v3"`
+fn import_name(spec: &str) -> Option<String> {
+    let spec = spec.split("//").next().unwrap_or(spec).trim();
+    let quote = spec.find('"')?;
+    let path = spec[quote..].trim_matches('"');
+    let alias = spec[..quote].trim();
+    if !alias.is_empty() {
+        return Some(alias.to_string());
+    }
+
+    let mut components = path.rsplit('/');
+    let mut last = components.next()?;
+    // Major version suffixes aren't part of the package name
+    if last.len() > 1 && last.starts_with('v') && last[1..].chars().all(|c| c.is_ascii_digit()) {
+        last = components.next().unwrap_or(last);
+    }
+    let last = last.split(".v").next().unwrap_or(last);
+    Some(last.trim_start_matches("go-").replace('-', "_"))
+}
+
+fn imports(state: &FileState) -> Vec<Import> {
+    let mut imports = Vec::new();
+    let mut block: Option<usize> = None;
+    let mut pending = Vec::new();
+
+    for line in state.non_blank_line_indices() {
+        let text = state.line(line).trim();
+        if let Some(open) = block {
+            if text.starts_with(')') {
+                for (spec_line, name) in pending.drain(..) {
+                    imports.push(Import { line: spec_line, name, block: Some((open, line)) });
+                }
+                block = None;
+            } else if let Some(name) = import_name(text) {
+                pending.push((line, name));
+            }
+        } else if let Some(rest) = text.strip_prefix("import") {
+            if rest.trim_start().starts_with('(') {
+                block = Some(line);
+            } else if let Some(name) = import_name(rest) {
+                imports.push(Import { line, name, block: None });
+            }
+        }
+    }
+    imports
+}
+
+/// Whether `name.` appears outside imports, strings and comments
+fn uses(state: &FileState, name: &str, import_lines: &[usize]) -> bool {
+    state
+        .non_blank_line_indices()
+        .into_iter()
+        .filter(|line| !import_lines.contains(line))
+        .any(|line| {
+            let text = state.line(line);
+            let tokens = tokenize(text);
+            tokens.windows(2).take_while(|pair| !(pair[0].text(text) == "/" && pair[1].text(text) == "/")).any(
+                |pair| pair[0].kind == TokenKind::Ident && pair[0].text(text) == name && pair[1].text(text) == ".",
+            )
+        })
+}
+
+/// Top-level declarations as line ranges [start, end), doc comments included
+fn declarations(state: &FileState) -> Vec<(usize, usize)> {
+    let lines = state.non_blank_line_indices();
+    let mut declarations = Vec::new();
+    let mut i = 0;
+
+    while i < lines.len() {
+        let text = state.line(lines[i]);
+        let is_declaration = ["func ", "type ", "var ", "const "]
+            .iter()
+            .any(|keyword| text.starts_with(keyword));
+        if !is_declaration {
+            i += 1;
+            continue;
+        }
+
+        let mut start = i;
+        while start > 0 && lines[start - 1] + 1 == lines[start] && state.line(lines[start - 1]).starts_with("//") {
+            start -= 1;
+        }
+        let mut end = i;
+        let mut depth = bracket_delta(text);
+        while depth > 0 && end + 1 < lines.len() {
+            end += 1;
+            depth += bracket_delta(state.line(lines[end]));
+        }
+
+        declarations.push((lines[start], lines[end] + 1));
+        i = end + 1;
+    }
+    declarations
+}
+
+/// Lines to blank along with lines [start, end) so no import is left unused
+fn unused_import_lines(state: &FileState, start: usize, end: usize) -> Vec<usize> {
+    let imports = imports(state);
+    let import_lines: Vec<usize> = imports.iter().map(|import| import.line).collect();
+    let mut after = state.clone();
+    after.blank_lines(&(start..end).collect::<Vec<_>>());
+
+    let unused: Vec<&Import> = imports
+        .iter()
+        .filter(|import| import.name != "_" && import.name != ".")
+        .filter(|import| uses(state, &import.name, &import_lines) && !uses(&after, &import.name, &import_lines))
+        .collect();
+
+    let mut lines: Vec<usize> = unused.iter().map(|import| import.line).collect();
+    // A block with nothing left in it goes too
+    for import in &unused {
+        if let Some((open, close)) = import.block {
+            let emptied = imports
+                .iter()
+                .filter(|other| other.block == import.block)
+                .all(|other| lines.contains(&other.line));
+            if emptied {
+                lines.extend([open, close]);
+            }
+        }
+    }
+    lines.sort();
+    lines.dedup();
+    lines
+}
+
+impl Strategy for GoDeclarationsStrategy {
+    fn name(&self) -> &str {
+        "go_decls"
+    }
+
+    fn generate_ranges(&self, _files: &HashMap<PathBuf, FileState>) -> Vec<ChompRange> {
+        // Declarations come with import pruning, see generate_chomps
+        Vec::new()
+    }
+
+    fn generate_chomps(&self, files: &HashMap<PathBuf, FileState>) -> Vec<Chomp> {
+        let mut paths: Vec<&PathBuf> = files
+            .keys()
+            .filter(|path| path.extension().is_some_and(|ext| ext == "go") && !files[*path].deleted)
+            .collect();
+        // Largest first, like file removal
+        paths.sort_by(|a, b| files[*b].non_blank_lines().cmp(&files[*a].non_blank_lines()).then(a.cmp(b)));
+
+        let mut chomps: Vec<Chomp> = paths.iter().map(|path| Chomp::DeleteFiles(vec![(*path).clone()])).collect();
+
+        let mut removals: Vec<(usize, Chomp)> = Vec::new();
+        for path in paths {
+            let state = &files[path];
+            for (start, end) in declarations(state) {
+                let range = |start_line: usize, end_line: usize| {
+                    Chomp::Range(ChompRange { file: path.clone(), start_line, end_line })
+                };
+                let pruned = unused_import_lines(state, start, end);
+                let chomp = if pruned.is_empty() {
+                    range(start, end)
+                } else {
+                    let mut parts = vec![range(start, end)];
+                    parts.extend(pruned.into_iter().map(|line| range(line, line + 1)));
+                    Chomp::Compound(parts)
+                };
+                removals.push((end - start, chomp));
+            }
+        }
+
+        // Largest declarations first; the sort is stable so ties stay in file order
+        removals.sort_by_key(|(size, _)| std::cmp::Reverse(*size));
+        chomps.extend(removals.into_iter().map(|(_, chomp)| chomp));
+        chomps
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAIN: &str = "package main\n\nimport (\n\t\"fmt\"\n\tstr \"strings\"\n)\n\n// Shout says hi\nfunc shout() {\n\tfmt.Println(str.ToUpper(\"hi\"))\n}\n\nfunc main() {\n\tfmt.Println(\"x\") // str.\n}";
+
+    #[test]
+    fn test_import_name() {
+        assert_eq!(import_name("\"fmt\""), Some("fmt".to_string()));
+        assert_eq!(import_name("r \"math/rand\""), Some("r".to_string()));
+        assert_eq!(import_name("\"gopkg.in/yaml.v3\""), Some("yaml".to_string()));
+        assert_eq!(import_name("\"github.com/a/b/v2\""), Some("b".to_string()));
+        assert_eq!(import_name("func main() {"), None);
+    }
+
+    #[test]
+    fn test_go_declarations() {
+        let state = FileState::new(PathBuf::from("main.go"), MAIN.to_string());
+        assert_eq!(declarations(&state), vec![(7, 11), (12, 15)]);
+        assert_eq!(
+            imports(&state),
+            vec![
+                Import { line: 3, name: "fmt".to_string(), block: Some((2, 5)) },
+                Import { line: 4, name: "str".to_string(), block: Some((2, 5)) },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_go_prunes_unused_imports() {
+        let state = FileState::new(PathBuf::from("main.go"), MAIN.to_string());
+        // `str` is only used by shout; the comment in main doesn't count
+        assert_eq!(unused_import_lines(&state, 7, 11), vec![4]);
+        assert!(unused_import_lines(&state, 12, 15).is_empty());
+
+        let mut files = HashMap::new();
+        files.insert(PathBuf::from("main.go"), state);
+        let described: Vec<String> =
+            GoDeclarationsStrategy.generate_chomps(&files).iter().map(|c| c.to_string()).collect();
+        assert_eq!(
+            described,
+            vec![
+                "delete 1 file(s): [\"main.go\"]",
+                "[\"main.go\" lines 7-11 (4 lines); \"main.go\" lines 4-5 (1 lines)]",
+                "\"main.go\" lines 12-15 (3 lines)",
+            ]
+        );
+    }
+}
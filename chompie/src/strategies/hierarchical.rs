@@ -0,0 +1,115 @@
+use crate::block_tree::Block;
+use crate::file_manager::FileState;
+use crate::strategy::{ChompRange, Strategy};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Hierarchical delta debugging (HDD) strategy: ddmin over a tree of nested blocks
+///
+/// Each file is parsed into a language-agnostic block tree (see `block_tree.rs`): top-level
+/// items, their inner blocks, then statements. Level by level from the root down, every
+/// list of siblings is chomped in ddmin style: halves, quarters, ... single siblings.
+/// Whole functions are therefore tried before any of their statements, and once a block
+/// is gone its children become no-op chomps that are skipped without running the command.
+pub struct HierarchicalStrategy;
+
+impl Strategy for HierarchicalStrategy {
+    fn name(&self) -> &str {
+        "hdd"
+    }
+
+    fn generate_ranges(&self, files: &HashMap<PathBuf, FileState>) -> Vec<ChompRange> {
+        let mut paths: Vec<&PathBuf> = files.keys().collect();
+        paths.sort();
+
+        let trees: Vec<(&PathBuf, Block)> = paths
+            .into_iter()
+            .map(|path| (path, Block::from_file(&files[path])))
+            .collect();
+        let levels: Vec<(&PathBuf, Vec<Vec<&[Block]>>)> =
+            trees.iter().map(|(path, tree)| (*path, tree.levels())).collect();
+        let depth = levels.iter().map(|(_, l)| l.len()).max().unwrap_or(0);
+
+        let mut ranges = Vec::new();
+
+        // Level-major across all files, so all top-level items go before any statements
+        for level in 0..depth {
+            for (path, file_levels) in &levels {
+                let Some(sibling_lists) = file_levels.get(level) else {
+                    continue;
+                };
+                for siblings in sibling_lists {
+                    push_ddmin_chunks(&mut ranges, path, siblings);
+                }
+            }
+        }
+
+        ranges
+    }
+}
+
+/// Chunks of consecutive siblings: halves, quarters, ... down to single siblings
+fn push_ddmin_chunks(ranges: &mut Vec<ChompRange>, path: &Path, siblings: &[Block]) {
+    let count = siblings.len();
+    let mut chunk_size = count.div_ceil(2);
+
+    loop {
+        for chunk in siblings.chunks(chunk_size) {
+            ranges.push(ChompRange {
+                file: path.to_path_buf(),
+                start_line: chunk[0].start_line,
+                end_line: chunk[chunk.len() - 1].end_line,
+            });
+        }
+        if chunk_size == 1 {
+            break;
+        }
+        chunk_size = chunk_size.div_ceil(2);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hdd_top_level_before_statements() {
+        let content = "fn a() {\n    one();\n    two();\n}\nfn b() {\n    three();\n}";
+        let path = PathBuf::from("test.rs");
+        let mut files = HashMap::new();
+        files.insert(path.clone(), FileState::new(path.clone(), content.to_string()));
+
+        let strategy = HierarchicalStrategy;
+        let ranges = strategy.generate_ranges(&files);
+        assert_eq!(strategy.name(), "hdd");
+
+        let spans: Vec<_> = ranges.iter().map(|r| (r.start_line, r.end_line)).collect();
+        assert_eq!(
+            spans,
+            vec![
+                // Level 0: halves of two functions are the single functions
+                (0, 4),
+                (4, 7),
+                // Level 1: body of a() in halves, then body of b()
+                (1, 2),
+                (2, 3),
+                (5, 6),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_ddmin_chunks() {
+        let siblings: Vec<Block> = (0..5)
+            .map(|i| Block { start_line: i, end_line: i + 1, children: Vec::new() })
+            .collect();
+        let mut ranges = Vec::new();
+        push_ddmin_chunks(&mut ranges, &PathBuf::from("test.txt"), &siblings);
+
+        let spans: Vec<_> = ranges.iter().map(|r| (r.start_line, r.end_line)).collect();
+        assert_eq!(
+            spans,
+            vec![(0, 3), (3, 5), (0, 2), (2, 4), (4, 5), (0, 1), (1, 2), (2, 3), (3, 4), (4, 5)]
+        );
+    }
+}
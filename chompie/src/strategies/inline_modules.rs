@@ -0,0 +1,198 @@
+use crate::file_manager::FileState;
+use crate::strategy::{Chomp, ChompRange, LineEdit, Strategy};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Inline modules strategy: flattens a Rust crate towards a single-file reproduction
+///
+/// Replaces `mod foo;` with `mod foo { ... }` holding the surviving lines of `foo.rs` (or
+/// `foo/mod.rs`) and deletes that file, as one chomp. Only modules without `mod` declarations
+/// of their own are inlined, so the crate is merged leaves first, one level per round, until
+/// everything lives in `main.rs`/`lib.rs`.
+pub struct InlineModulesStrategy;
+
+/// Visibility (with trailing space) and name of a `mod name;` line
+fn module_declaration(line: &str) -> Option<(&str, &str)> {
+    let body = line.trim().strip_suffix(';')?.trim_end();
+    let (visibility, name) = body.rsplit_once("mod ")?;
+    let valid_name = !name.is_empty() && name.chars().all(|c| c.is_alphanumeric() || c == '_');
+    let valid_visibility = visibility.is_empty() || (visibility.starts_with("pub") && visibility.ends_with(' '));
+    (valid_name && valid_visibility).then_some((visibility, name))
+}
+
+/// Files a `mod name;` in `parent` can refer to
+fn module_paths(parent: &Path, name: &str) -> [PathBuf; 2] {
+    let dir = parent.parent().unwrap_or(Path::new(""));
+    let base = match parent.file_stem().and_then(|stem| stem.to_str()) {
+        Some("main" | "lib" | "mod") | None => dir.to_path_buf(),
+        Some(stem) => dir.join(stem),
+    };
+    [base.join(format!("{}.rs", name)), base.join(name).join("mod.rs")]
+}
+
+/// Surviving lines of a module, each prefixed with `indent`
+fn module_body(state: &FileState, indent: &str) -> Vec<String> {
+    state
+        .non_blank_line_indices()
+        .into_iter()
+        .flat_map(|i| state.line(i).lines())
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| format!("{}{}", indent, line))
+        .collect()
+}
+
+impl Strategy for InlineModulesStrategy {
+    fn name(&self) -> &str {
+        "inline_modules"
+    }
+
+    fn generate_ranges(&self, _files: &HashMap<PathBuf, FileState>) -> Vec<ChompRange> {
+        // Moves code between files rather than blanking it
+        Vec::new()
+    }
+
+    fn generate_chomps(&self, files: &HashMap<PathBuf, FileState>) -> Vec<Chomp> {
+        let mut paths: Vec<&PathBuf> = files
+            .keys()
+            .filter(|path| path.extension().is_some_and(|ext| ext == "rs"))
+            .collect();
+        paths.sort();
+
+        let has_declarations = |state: &FileState| {
+            state
+                .non_blank_line_indices()
+                .into_iter()
+                .any(|i| module_declaration(state.line(i)).is_some())
+        };
+
+        let mut chomps = Vec::new();
+
+        for parent in paths {
+            let state = &files[parent];
+            let mut previous = "";
+            for line in state.non_blank_line_indices() {
+                let text = state.line(line);
+                // `#[path = ...]` points somewhere else entirely
+                let has_path_attribute = previous.trim_start().starts_with("#[path");
+                previous = text;
+                let Some((visibility, name)) = module_declaration(text) else {
+                    continue;
+                };
+                if has_path_attribute {
+                    continue;
+                }
+                let Some(child) = module_paths(parent, name)
+                    .into_iter()
+                    .filter_map(|path| files.get(&path))
+                    .find(|child| !child.deleted && child.path != *parent)
+                else {
+                    continue;
+                };
+                // Leaves first: the child's own `mod` lines would resolve differently once inlined
+                if has_declarations(child) {
+                    continue;
+                }
+
+                let indent: String = text.chars().take_while(|c| c.is_whitespace()).collect();
+                let header = format!("{}{}mod {} {{", indent, visibility, name);
+                let footer = format!("{}}}", indent);
+                // Indenting can change multi-line string literals, so fall back to verbatim
+                let mut bodies = vec![module_body(child, &format!("{}    ", indent))];
+                let verbatim = module_body(child, "");
+                if verbatim != bodies[0] {
+                    bodies.push(verbatim);
+                }
+
+                for body in bodies {
+                    let replacement = std::iter::once(header.clone())
+                        .chain(body)
+                        .chain(std::iter::once(footer.clone()))
+                        .collect::<Vec<_>>()
+                        .join("\n");
+                    chomps.push(Chomp::Compound(vec![
+                        Chomp::Edit(LineEdit {
+                            file: parent.clone(),
+                            line,
+                            expected: text.to_string(),
+                            replacement,
+                        }),
+                        Chomp::DeleteFiles(vec![child.path.clone()]),
+                    ]));
+                }
+            }
+        }
+
+        chomps
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn files(entries: &[(&str, &str)]) -> HashMap<PathBuf, FileState> {
+        entries
+            .iter()
+            .map(|(path, content)| (PathBuf::from(path), FileState::new(PathBuf::from(path), content.to_string())))
+            .collect()
+    }
+
+    #[test]
+    fn test_module_declaration() {
+        assert_eq!(module_declaration("mod foo;"), Some(("", "foo")));
+        assert_eq!(module_declaration("    pub(crate) mod foo_bar;"), Some(("pub(crate) ", "foo_bar")));
+        assert_eq!(module_declaration("mod foo {"), None);
+        assert_eq!(module_declaration("use foo::mod bar;"), None);
+    }
+
+    #[test]
+    fn test_inline_leaf_modules_first() {
+        let files = files(&[
+            ("src/main.rs", "mod a;\npub mod b;\n\nfn main() {}"),
+            ("src/a.rs", "pub fn f() {}\n\npub fn g() {}"),
+            ("src/b/mod.rs", "mod c;"),
+            ("src/b/c.rs", "fn h() {}"),
+        ]);
+        let chomps = InlineModulesStrategy.generate_chomps(&files);
+
+        let inlined: Vec<(PathBuf, String, Vec<PathBuf>)> = chomps
+            .into_iter()
+            .map(|chomp| match chomp {
+                Chomp::Compound(parts) => match (&parts[0], &parts[1]) {
+                    (Chomp::Edit(edit), Chomp::DeleteFiles(deleted)) => {
+                        (edit.file.clone(), edit.replacement.clone(), deleted.clone())
+                    }
+                    _ => panic!("unexpected parts"),
+                },
+                other => panic!("unexpected chomp: {}", other),
+            })
+            .collect();
+
+        // `b` still declares `c`, so only `a` and `c` are inlined this round
+        assert_eq!(
+            inlined,
+            vec![
+                (
+                    PathBuf::from("src/b/mod.rs"),
+                    "mod c {\n    fn h() {}\n}".to_string(),
+                    vec![PathBuf::from("src/b/c.rs")]
+                ),
+                (
+                    PathBuf::from("src/b/mod.rs"),
+                    "mod c {\nfn h() {}\n}".to_string(),
+                    vec![PathBuf::from("src/b/c.rs")]
+                ),
+                (
+                    PathBuf::from("src/main.rs"),
+                    "mod a {\n    pub fn f() {}\n    pub fn g() {}\n}".to_string(),
+                    vec![PathBuf::from("src/a.rs")]
+                ),
+                (
+                    PathBuf::from("src/main.rs"),
+                    "mod a {\npub fn f() {}\npub fn g() {}\n}".to_string(),
+                    vec![PathBuf::from("src/a.rs")]
+                ),
+            ]
+        );
+    }
+}
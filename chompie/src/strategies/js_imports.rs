@@ -0,0 +1,235 @@
+use crate::file_manager::FileState;
+use crate::strategy::{Chomp, ChompRange, Strategy};
+use crate::tokens::{tokenize, TokenKind};
+use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
+
+/// JS imports strategy: removes JavaScript/TypeScript modules along with everything that
+/// imports them
+///
+/// Follows `import ... from`, `export ... from`, side-effect `import '...'`, `require()` and
+/// dynamic `import()` of relative specifiers. For each imported module it proposes deleting
+/// the file and blanking every statement that references it as one chomp, then each of those
+/// statements on its own.
+pub struct JsImportsStrategy;
+
+/// An import statement referencing a module file
+#[derive(Debug, PartialEq, Eq)]
+struct Reference {
+    file: PathBuf,
+    /// Lines [start_line, end_line) holding the statement
+    start_line: usize,
+    end_line: usize,
+    target: PathBuf,
+}
+
+/// Module specifiers a line loads
+fn specifiers(line: &str) -> Vec<&str> {
+    let tokens = tokenize(line);
+    let text = |i: usize| tokens[i].text(line);
+
+    (0..tokens.len())
+        .filter(|&i| tokens[i].kind == TokenKind::Str && i > 0)
+        .filter(|&i| match text(i - 1) {
+            "from" | "import" => true,
+            "(" => i > 1 && matches!(text(i - 2), "require" | "import"),
+            _ => false,
+        })
+        .map(|i| {
+            let literal = text(i);
+            literal.get(1..literal.len() - 1).unwrap_or("")
+        })
+        .collect()
+}
+
+/// Normalize `a/./b/../c` to `a/c` without touching the filesystem
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if !normalized.pop() {
+                    normalized.push("..");
+                }
+            }
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
+
+/// The file a relative specifier imported from `importer` refers to, if it's being chomped
+/// `paths` maps normalized paths to the keys of `files`
+fn resolve(
+    specifier: &str,
+    importer: &Path,
+    files: &HashMap<PathBuf, FileState>,
+    paths: &HashMap<PathBuf, &PathBuf>,
+) -> Option<PathBuf> {
+    // Packages and absolute URLs aren't ours to remove
+    if !specifier.starts_with("./") && !specifier.starts_with("../") {
+        return None;
+    }
+    let base = normalize(&importer.parent().unwrap_or(Path::new("")).join(specifier));
+    let mut candidates = vec![base.clone()];
+    for extension in ["js", "ts"] {
+        // TypeScript imports `./foo.js` when it means `./foo.ts`
+        candidates.push(base.with_extension(extension));
+        candidates.push(PathBuf::from(format!("{}.{}", base.display(), extension)));
+        candidates.push(base.join(format!("index.{}", extension)));
+    }
+    candidates
+        .iter()
+        .filter_map(|candidate| paths.get(candidate).copied())
+        .find(|path| !files[*path].deleted && path.as_path() != importer)
+        .cloned()
+}
+
+/// First line of the statement ending on `line`, for imports spread over several lines
+fn statement_start(state: &FileState, line: usize) -> usize {
+    let starts_statement = |text: &str| {
+        let first = text.trim_start();
+        first.starts_with("import") || first.starts_with("export")
+    };
+    if starts_statement(state.line(line)) || !state.line(line).trim_start().starts_with('}') {
+        return line;
+    }
+    (0..line)
+        .rev()
+        .take_while(|&i| !state.line(i).contains(';'))
+        .find(|&i| starts_statement(state.line(i)))
+        .unwrap_or(line)
+}
+
+fn references(files: &HashMap<PathBuf, FileState>) -> Vec<Reference> {
+    let mut paths: Vec<&PathBuf> = files
+        .keys()
+        .filter(|path| path.extension().is_some_and(|ext| ext == "js" || ext == "ts"))
+        .collect();
+    paths.sort();
+    let normalized: HashMap<PathBuf, &PathBuf> = files.keys().map(|path| (normalize(path), path)).collect();
+
+    let mut references = Vec::new();
+    for path in paths {
+        let state = &files[path];
+        for line in state.non_blank_line_indices() {
+            for specifier in specifiers(state.line(line)) {
+                if let Some(target) = resolve(specifier, path, files, &normalized) {
+                    references.push(Reference {
+                        file: path.clone(),
+                        start_line: statement_start(state, line),
+                        end_line: line + 1,
+                        target,
+                    });
+                }
+            }
+        }
+    }
+    references
+}
+
+impl Strategy for JsImportsStrategy {
+    fn name(&self) -> &str {
+        "js_imports"
+    }
+
+    fn generate_ranges(&self, _files: &HashMap<PathBuf, FileState>) -> Vec<ChompRange> {
+        // Removes modules together with their imports, see generate_chomps
+        Vec::new()
+    }
+
+    fn generate_chomps(&self, files: &HashMap<PathBuf, FileState>) -> Vec<Chomp> {
+        let references = references(files);
+
+        // Imported modules, largest first like file removal
+        let mut targets: Vec<&PathBuf> = references.iter().map(|r| &r.target).collect();
+        targets.sort_by(|a, b| files[*b].non_blank_lines().cmp(&files[*a].non_blank_lines()).then(a.cmp(b)));
+        targets.dedup();
+
+        let blank = |reference: &Reference| {
+            Chomp::Range(ChompRange {
+                file: reference.file.clone(),
+                start_line: reference.start_line,
+                end_line: reference.end_line,
+            })
+        };
+        let mut chomps = Vec::new();
+
+        for target in targets {
+            // Imports inside the deleted module go with it
+            let mut parts: Vec<Chomp> = references
+                .iter()
+                .filter(|r| r.target == *target && r.file != *target)
+                .map(blank)
+                .collect();
+            parts.push(Chomp::DeleteFiles(vec![target.clone()]));
+            chomps.push(Chomp::Compound(parts));
+        }
+
+        chomps.extend(references.iter().map(blank));
+        chomps
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_specifiers() {
+        assert_eq!(specifiers("import { a, b } from './a.js';"), vec!["./a.js"]);
+        assert_eq!(specifiers("import './side-effect';"), vec!["./side-effect"]);
+        assert_eq!(specifiers("export * from \"../lib\";"), vec!["../lib"]);
+        assert_eq!(specifiers("const x = require('./x'), y = await import('./y');"), vec!["./x", "./y"]);
+        assert!(specifiers("console.log('from', 'import')").is_empty());
+    }
+
+    #[test]
+    fn test_resolve_with_dot_prefix() {
+        let mut files = HashMap::new();
+        for path in ["./main.js", "./lib/a.js"] {
+            files.insert(PathBuf::from(path), FileState::new(PathBuf::from(path), String::new()));
+        }
+        let paths: HashMap<PathBuf, &PathBuf> = files.keys().map(|path| (normalize(path), path)).collect();
+        let importer = Path::new("./main.js");
+        assert_eq!(resolve("./lib/a", importer, &files, &paths), Some(PathBuf::from("./lib/a.js")));
+        assert_eq!(resolve("./lib/b", importer, &files, &paths), None);
+        assert_eq!(resolve("lodash", importer, &files, &paths), None);
+    }
+
+    #[test]
+    fn test_js_imports_strategy() {
+        let mut files = HashMap::new();
+        for (path, content) in [
+            ("src/main.ts", "import {\n  helper,\n} from './util.js';\nimport fs from 'fs';\nhelper();"),
+            ("src/other.js", "const util = require('./util');\nconst lib = require('../lib');"),
+            ("src/util.ts", "export function helper() {}"),
+            ("lib/index.js", "module.exports = {};"),
+        ] {
+            files.insert(PathBuf::from(path), FileState::new(PathBuf::from(path), content.to_string()));
+        }
+
+        let described: Vec<String> = JsImportsStrategy
+            .generate_chomps(&files)
+            .iter()
+            .map(|chomp| match chomp {
+                Chomp::Compound(parts) => parts.iter().map(|p| p.to_string()).collect::<Vec<_>>().join(" + "),
+                Chomp::Range(range) => format!("{:?} {}-{}", range.file, range.start_line, range.end_line),
+                other => panic!("unexpected chomp: {}", other),
+            })
+            .collect();
+
+        assert_eq!(
+            described,
+            vec![
+                "\"src/other.js\" lines 1-2 (1 lines) + delete 1 file(s): [\"lib/index.js\"]",
+                "\"src/main.ts\" lines 0-3 (3 lines) + \"src/other.js\" lines 0-1 (1 lines) \
+                 + delete 1 file(s): [\"src/util.ts\"]",
+                "\"src/main.ts\" 0-3",
+                "\"src/other.js\" 0-1",
+                "\"src/other.js\" 1-2",
+            ]
+        );
+    }
+}
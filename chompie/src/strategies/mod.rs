@@ -1,9 +1,37 @@
 mod bisection;
+mod coverage;
+mod file_removal;
+mod go_decls;
+mod hierarchical;
+mod inline_modules;
+mod js_imports;
+mod preprocessor;
+mod python_imports;
 mod random_lines;
 mod random_ranges;
+mod simplify;
+mod stubs;
+mod symbols;
+mod tokens;
+#[cfg(feature = "tree-sitter")]
+mod tree_sitter;
 mod up_to_n_lines;
 
 pub use bisection::BisectionStrategy;
+pub use coverage::CoverageStrategy;
+pub use file_removal::FileRemovalStrategy;
+pub use go_decls::GoDeclarationsStrategy;
+pub use hierarchical::HierarchicalStrategy;
+pub use inline_modules::InlineModulesStrategy;
+pub use js_imports::JsImportsStrategy;
+pub use preprocessor::{conditionals_balanced, strands_conditionals, PreprocessorStrategy};
+pub use python_imports::PythonImportsStrategy;
 pub use random_lines::RandomLinesStrategy;
 pub use random_ranges::RandomRangesStrategy;
+pub use simplify::{Simplification, SimplifyStrategy};
+pub use stubs::StubStrategy;
+pub use symbols::SymbolsStrategy;
+pub use tokens::{Granularity, TokenStrategy};
+#[cfg(feature = "tree-sitter")]
+pub use tree_sitter::TreeSitterStrategy;
 pub use up_to_n_lines::UpToNLinesStrategy;
@@ -0,0 +1,269 @@
+use crate::file_manager::FileState;
+use crate::strategy::{Chomp, ChompRange, Strategy};
+use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
+
+/// Preprocessor strategy: C/C++ reduction that respects `#if` structure
+///
+/// Tries, in order: removing a header together with every `#include` of it, removing whole
+/// `#if`/`#ifdef`/`#ifndef ... #endif` regions (largest first), removing the `#elif`/`#else`
+/// branches of a region, and dropping single `#include` lines. Every candidate keeps
+/// conditionals matched; candidates of any strategy that would leave an unmatched one are
+/// rejected by `strands_conditionals`.
+pub struct PreprocessorStrategy;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Directive {
+    /// `#if`, `#ifdef`, `#ifndef`
+    If,
+    /// `#elif`, `#else`
+    Else,
+    Endif,
+    Include,
+}
+
+fn directive(line: &str) -> Option<Directive> {
+    let rest = line.trim_start().strip_prefix('#')?.trim_start();
+    let keyword: String = rest.chars().take_while(|c| c.is_ascii_alphabetic()).collect();
+    match keyword.as_str() {
+        "if" | "ifdef" | "ifndef" => Some(Directive::If),
+        "elif" | "elifdef" | "elifndef" | "else" => Some(Directive::Else),
+        "endif" => Some(Directive::Endif),
+        "include" => Some(Directive::Include),
+        _ => None,
+    }
+}
+
+/// Whether every `#if` has its `#endif`, with `#else`/`#elif` only inside one
+pub fn conditionals_balanced(content: &str) -> bool {
+    let mut depth = 0;
+    for line in content.lines() {
+        match directive(line) {
+            Some(Directive::If) => depth += 1,
+            Some(Directive::Else) if depth == 0 => return false,
+            Some(Directive::Endif) => {
+                if depth == 0 {
+                    return false;
+                }
+                depth -= 1;
+            }
+            _ => {}
+        }
+    }
+    depth == 0
+}
+
+fn is_c_file(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == "c" || ext == "cpp" || ext == "h")
+}
+
+/// Whether a chomp left an unmatched conditional in a C/C++ file whose conditionals matched
+/// before; `before` holds the changed files as they were
+/// The chomper checks every strategy's candidates with this, since line-based strategies like
+/// bisection know nothing of `#if` structure.
+pub fn strands_conditionals(before: &[FileState], files: &HashMap<PathBuf, FileState>) -> bool {
+    before
+        .iter()
+        .filter(|old| is_c_file(&old.path) && conditionals_balanced(&old.current_content()))
+        .filter_map(|old| files.get(&old.path))
+        .any(|new| !new.deleted && !conditionals_balanced(&new.current_content()))
+}
+
+/// A conditional region: the `#if` line, any `#elif`/`#else` lines, and the `#endif` line
+struct Region {
+    start: usize,
+    branches: Vec<usize>,
+    end: usize,
+}
+
+fn regions(state: &FileState) -> Vec<Region> {
+    let mut open: Vec<Region> = Vec::new();
+    let mut regions = Vec::new();
+    for line in state.non_blank_line_indices() {
+        match directive(state.line(line)) {
+            Some(Directive::If) => open.push(Region { start: line, branches: Vec::new(), end: line }),
+            Some(Directive::Else) => {
+                if let Some(region) = open.last_mut() {
+                    region.branches.push(line);
+                }
+            }
+            Some(Directive::Endif) => {
+                if let Some(mut region) = open.pop() {
+                    region.end = line;
+                    regions.push(region);
+                }
+            }
+            _ => {}
+        }
+    }
+    regions
+}
+
+/// The header an `#include` line refers to, if it's being chomped
+fn resolve_include(line: &str, includer: &Path, files: &HashMap<PathBuf, FileState>) -> Option<PathBuf> {
+    let rest = line.trim_start().strip_prefix('#')?.trim_start().strip_prefix("include")?.trim();
+    let close = match rest.chars().next()? {
+        '"' => '"',
+        '<' => '>',
+        _ => return None,
+    };
+    let name = &rest[1..rest[1..].find(close)? + 1];
+
+    // Next to the includer first, then anywhere in the include path by suffix
+    let local = includer.parent().unwrap_or(Path::new("")).join(name);
+    if files.contains_key(&local) {
+        return Some(local).filter(|path| !files[path].deleted);
+    }
+    let suffix: PathBuf = Path::new(name)
+        .components()
+        .filter(|component| matches!(component, Component::Normal(_)))
+        .collect();
+    let mut matches = files.keys().filter(|path| path.ends_with(&suffix));
+    match (matches.next(), matches.next()) {
+        (Some(path), None) if !files[path].deleted => Some(path.clone()),
+        _ => None,
+    }
+}
+
+impl Strategy for PreprocessorStrategy {
+    fn name(&self) -> &str {
+        "preprocessor"
+    }
+
+    fn generate_ranges(&self, files: &HashMap<PathBuf, FileState>) -> Vec<ChompRange> {
+        let mut paths: Vec<&PathBuf> = files.keys().filter(|path| is_c_file(path)).collect();
+        paths.sort();
+
+        let range = |file: &PathBuf, start_line: usize, end_line: usize| ChompRange {
+            file: file.clone(),
+            start_line,
+            end_line,
+        };
+        let mut region_ranges = Vec::new();
+        let mut include_ranges = Vec::new();
+
+        for path in paths {
+            let state = &files[path];
+            for region in regions(state) {
+                region_ranges.push(range(path, region.start, region.end + 1));
+                // Each `#elif`/`#else` branch up to the next one, or the `#endif`
+                for (i, &branch) in region.branches.iter().enumerate() {
+                    let next = region.branches.get(i + 1).copied().unwrap_or(region.end);
+                    region_ranges.push(range(path, branch, next));
+                }
+            }
+            for line in state.non_blank_line_indices() {
+                if directive(state.line(line)) == Some(Directive::Include) {
+                    include_ranges.push(range(path, line, line + 1));
+                }
+            }
+        }
+
+        // Largest regions first; the sort is stable so ties stay in file order
+        region_ranges.sort_by_key(|r| std::cmp::Reverse(r.end_line - r.start_line));
+        region_ranges.extend(include_ranges);
+        region_ranges
+    }
+
+    fn generate_chomps(&self, files: &HashMap<PathBuf, FileState>) -> Vec<Chomp> {
+        let mut paths: Vec<&PathBuf> = files.keys().filter(|path| is_c_file(path)).collect();
+        paths.sort();
+
+        // Every include of a header being chomped
+        let mut includes: Vec<(PathBuf, usize, PathBuf)> = Vec::new();
+        for path in paths {
+            let state = &files[path];
+            for line in state.non_blank_line_indices() {
+                if let Some(header) = resolve_include(state.line(line), path, files) {
+                    if header != *path {
+                        includes.push((path.clone(), line, header));
+                    }
+                }
+            }
+        }
+
+        // Included headers, largest first like file removal
+        let mut headers: Vec<&PathBuf> = includes.iter().map(|(_, _, header)| header).collect();
+        headers.sort_by(|a, b| files[*b].non_blank_lines().cmp(&files[*a].non_blank_lines()).then(a.cmp(b)));
+        headers.dedup();
+
+        let mut chomps: Vec<Chomp> = headers
+            .into_iter()
+            .map(|header| {
+                let mut parts: Vec<Chomp> = includes
+                    .iter()
+                    .filter(|(file, _, included)| included == header && file != header)
+                    .map(|(file, line, _)| {
+                        Chomp::Range(ChompRange { file: file.clone(), start_line: *line, end_line: line + 1 })
+                    })
+                    .collect();
+                parts.push(Chomp::DeleteFiles(vec![header.clone()]));
+                Chomp::Compound(parts)
+            })
+            .collect();
+
+        chomps.extend(self.generate_ranges(files).into_iter().map(Chomp::Range));
+        chomps
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAIN: &str = "#include \"util.h\"\n#include <stdio.h>\n#ifdef DEBUG\nlog();\n#else\nquiet();\n#endif\nint main() {}";
+
+    fn files(entries: &[(&str, &str)]) -> HashMap<PathBuf, FileState> {
+        entries
+            .iter()
+            .map(|(path, content)| (PathBuf::from(path), FileState::new(PathBuf::from(path), content.to_string())))
+            .collect()
+    }
+
+    #[test]
+    fn test_conditionals_balanced() {
+        assert!(conditionals_balanced(MAIN));
+        assert!(conditionals_balanced("#ifndef A_H\n#define A_H\n# if X\n# endif\n#endif"));
+        assert!(!conditionals_balanced("#ifdef DEBUG\nlog();\n"));
+        assert!(!conditionals_balanced("log();\n#endif"));
+        assert!(!conditionals_balanced("#else\n"));
+    }
+
+    #[test]
+    fn test_preprocessor_chomps() {
+        let files = files(&[("src/main.c", MAIN), ("src/util.h", "#pragma once\nvoid util();")]);
+        let described: Vec<String> = PreprocessorStrategy
+            .generate_chomps(&files)
+            .iter()
+            .map(|chomp| chomp.to_string())
+            .collect();
+
+        assert_eq!(
+            described,
+            vec![
+                "[\"src/main.c\" lines 0-1 (1 lines); delete 1 file(s): [\"src/util.h\"]]",
+                "\"src/main.c\" lines 2-7 (5 lines)",
+                "\"src/main.c\" lines 4-6 (2 lines)",
+                "\"src/main.c\" lines 0-1 (1 lines)",
+                "\"src/main.c\" lines 1-2 (1 lines)",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_strands_conditionals() {
+        let mut files = files(&[("main.c", MAIN), ("broken.h", "#endif\nint x;"), ("notes.txt", "#if\n")]);
+        let before: Vec<FileState> = files.values().cloned().collect();
+        assert!(!strands_conditionals(&before, &files));
+
+        files.get_mut(Path::new("main.c")).unwrap().blank_lines(&[2]);
+        assert!(strands_conditionals(&before, &files));
+        files.get_mut(Path::new("main.c")).unwrap().deleted = true;
+        assert!(!strands_conditionals(&before, &files));
+
+        // Only files whose conditionals matched to begin with, and only C/C++ ones
+        files.get_mut(Path::new("broken.h")).unwrap().blank_lines(&[1]);
+        files.get_mut(Path::new("notes.txt")).unwrap().blank_lines(&[0]);
+        assert!(!strands_conditionals(&before, &files));
+    }
+}
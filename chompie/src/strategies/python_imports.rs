@@ -0,0 +1,257 @@
+use crate::file_manager::FileState;
+use crate::strategy::{Chomp, ChompRange, LineEdit, Strategy};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Python imports strategy: removes import statements, and the modules they import
+///
+/// For every `import x` / `from x import y` line it first tries removing the imported
+/// module files (when they're being chomped) together with every import of them, then the
+/// import line on its own, and finally dropping single names from multi-name import lists.
+pub struct PythonImportsStrategy;
+
+/// A single-line import statement
+#[derive(Debug, PartialEq, Eq)]
+struct Import {
+    /// Dotted module names this line may load, relative ones with leading dots
+    modules: Vec<String>,
+    /// Byte spans of the imported names (`a`, `b as c`, ...)
+    names: Vec<(usize, usize)>,
+}
+
+fn parse_import(line: &str) -> Option<Import> {
+    let offset = line.len() - line.trim_start().len();
+    let statement = line.trim();
+    // Names start right after the keyword that introduces them
+    let (from, names_start) = if let Some(rest) = statement.strip_prefix("import ") {
+        (None, statement.len() - rest.len())
+    } else {
+        let rest = statement.strip_prefix("from ")?;
+        let (module, _) = rest.split_once(" import ")?;
+        let module = module.trim();
+        if module == "__future__" {
+            return None;
+        }
+        (Some(module), statement.find(" import ")? + " import ".len())
+    };
+
+    // Strip a trailing comment and the parentheses of `from x import (a, b)`
+    let mut list = &statement[names_start..];
+    let mut start = names_start;
+    if let Some(comment) = list.find('#') {
+        list = &list[..comment];
+    }
+    if let Some(inner) = list.trim().strip_prefix('(').and_then(|inner| inner.strip_suffix(')')) {
+        start += list.find('(')? + 1;
+        list = inner;
+    } else if list.contains('(') || list.trim_end().ends_with('\\') {
+        // Continues on the next lines
+        return None;
+    }
+
+    let mut names = Vec::new();
+    let mut item_start = 0;
+    for item in list.split(',') {
+        let leading = item.len() - item.trim_start().len();
+        let trimmed = item.trim();
+        if !trimmed.is_empty() {
+            let begin = offset + start + item_start + leading;
+            names.push((begin, begin + trimmed.len()));
+        }
+        item_start += item.len() + 1;
+    }
+    if names.is_empty() {
+        return None;
+    }
+
+    let name = |&(begin, end): &(usize, usize)| {
+        let item = &line[begin..end];
+        item.split(" as ").next().unwrap_or(item).trim().to_string()
+    };
+    let modules = match from {
+        // The names may be submodules as well as attributes
+        Some(module) => {
+            let separator = if module.ends_with('.') { "" } else { "." };
+            std::iter::once(module.to_string())
+                .chain(names.iter().map(|n| format!("{}{}{}", module, separator, name(n))))
+                .collect()
+        }
+        None => names.iter().map(name).collect(),
+    };
+
+    Some(Import { modules, names })
+}
+
+/// Files among `files` that a module name imported from `importer` can resolve to
+fn resolve(module: &str, importer: &Path, files: &HashMap<PathBuf, FileState>) -> Vec<PathBuf> {
+    let dots = module.len() - module.trim_start_matches('.').len();
+    let parts: Vec<&str> = module[dots..].split('.').filter(|p| !p.is_empty()).collect();
+    // `from . import x` names the package itself; leave its `__init__.py` alone
+    if parts.is_empty() {
+        return Vec::new();
+    }
+    let relative: PathBuf = parts.iter().collect();
+    let candidates = [relative.with_extension("py"), relative.join("__init__.py")];
+
+    let mut resolved: Vec<PathBuf> = if dots > 0 {
+        let mut base = importer.parent().unwrap_or(Path::new("")).to_path_buf();
+        for _ in 1..dots {
+            base = base.parent().unwrap_or(Path::new("")).to_path_buf();
+        }
+        candidates
+            .iter()
+            .map(|candidate| base.join(candidate))
+            .filter(|path| files.contains_key(path))
+            .collect()
+    } else {
+        // Absolute imports resolve against an unknown sys.path, so match by suffix
+        files
+            .keys()
+            .filter(|path| candidates.iter().any(|candidate| path.ends_with(candidate)))
+            .cloned()
+            .collect()
+    };
+    resolved.retain(|path| path != importer && !files[path].deleted);
+    resolved.sort();
+    resolved
+}
+
+impl Strategy for PythonImportsStrategy {
+    fn name(&self) -> &str {
+        "python_imports"
+    }
+
+    fn generate_ranges(&self, _files: &HashMap<PathBuf, FileState>) -> Vec<ChompRange> {
+        // Removes imports together with files, see generate_chomps
+        Vec::new()
+    }
+
+    fn generate_chomps(&self, files: &HashMap<PathBuf, FileState>) -> Vec<Chomp> {
+        let mut paths: Vec<&PathBuf> = files
+            .keys()
+            .filter(|path| path.extension().is_some_and(|ext| ext == "py"))
+            .collect();
+        paths.sort();
+
+        // Every import line, with the files it loads
+        let mut imports: Vec<(&PathBuf, usize, Import, Vec<PathBuf>)> = Vec::new();
+        for path in paths {
+            let state = &files[path];
+            for line in state.non_blank_line_indices() {
+                if let Some(import) = parse_import(state.line(line)) {
+                    let mut targets: Vec<PathBuf> = import
+                        .modules
+                        .iter()
+                        .flat_map(|module| resolve(module, path, files))
+                        .collect();
+                    targets.sort();
+                    targets.dedup();
+                    imports.push((path, line, import, targets));
+                }
+            }
+        }
+
+        let blank = |file: &PathBuf, line: usize| {
+            Chomp::Range(ChompRange { file: file.clone(), start_line: line, end_line: line + 1 })
+        };
+        let mut chomps = Vec::new();
+
+        for (path, line, _, targets) in &imports {
+            if !targets.is_empty() {
+                // The modules go, and so does every import of them
+                let mut parts: Vec<Chomp> = imports
+                    .iter()
+                    .filter(|(other, _, _, other_targets)| {
+                        !targets.contains(other) && other_targets.iter().any(|t| targets.contains(t))
+                    })
+                    .map(|(other, other_line, _, _)| blank(other, *other_line))
+                    .collect();
+                parts.push(Chomp::DeleteFiles(targets.clone()));
+                chomps.push(Chomp::Compound(parts));
+            }
+            chomps.push(blank(path, *line));
+        }
+
+        for (path, line, import, _) in &imports {
+            if import.names.len() < 2 {
+                continue;
+            }
+            let text = files[*path].line(*line);
+            let (list_start, list_end) = (import.names[0].0, import.names[import.names.len() - 1].1);
+            for skip in 0..import.names.len() {
+                let kept: Vec<&str> = import
+                    .names
+                    .iter()
+                    .enumerate()
+                    .filter(|&(i, _)| i != skip)
+                    .map(|(_, &(begin, end))| &text[begin..end])
+                    .collect();
+                chomps.push(Chomp::Edit(LineEdit {
+                    file: (*path).clone(),
+                    line: *line,
+                    expected: text.to_string(),
+                    replacement: format!("{}{}{}", &text[..list_start], kept.join(", "), &text[list_end..]),
+                }));
+            }
+        }
+
+        chomps
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_import() {
+        let import = parse_import("import os, numpy as np").unwrap();
+        assert_eq!(import.modules, vec!["os", "numpy"]);
+        assert_eq!(import.names, vec![(7, 9), (11, 22)]);
+
+        let import = parse_import("    from .util import (a, b)  # helpers").unwrap();
+        assert_eq!(import.modules, vec![".util", ".util.a", ".util.b"]);
+        assert_eq!(import.names, vec![(23, 24), (26, 27)]);
+
+        assert_eq!(parse_import("from x import (").map(|i| i.names), None);
+        assert_eq!(parse_import("from __future__ import annotations"), None);
+        assert_eq!(parse_import("important = 1"), None);
+    }
+
+    #[test]
+    fn test_python_imports_strategy() {
+        let mut files = HashMap::new();
+        for (path, content) in [
+            ("pkg/main.py", "from .helpers import f, g\nimport os\nprint(f())"),
+            ("pkg/other.py", "from pkg.helpers import g"),
+            ("pkg/helpers.py", "def f(): return 1\ndef g(): return 2"),
+        ] {
+            files.insert(PathBuf::from(path), FileState::new(PathBuf::from(path), content.to_string()));
+        }
+
+        let described: Vec<String> = PythonImportsStrategy
+            .generate_chomps(&files)
+            .iter()
+            .map(|chomp| match chomp {
+                Chomp::Compound(parts) => format!("{} parts", parts.len()),
+                Chomp::Range(range) => format!("{}:{}", range.file.display(), range.start_line),
+                Chomp::Edit(edit) => edit.replacement.clone(),
+                other => panic!("unexpected chomp: {}", other),
+            })
+            .collect();
+
+        assert_eq!(
+            described,
+            vec![
+                // helpers.py with the imports of it in main.py and other.py
+                "3 parts",
+                "pkg/main.py:0",
+                "pkg/main.py:1",
+                "3 parts",
+                "pkg/other.py:0",
+                "from .helpers import g",
+                "from .helpers import f",
+            ]
+        );
+    }
+}
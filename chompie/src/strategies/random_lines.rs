@@ -19,6 +19,7 @@ impl RandomLinesStrategy {
         }
     }
 
+    #[cfg(test)]
     pub fn with_seed(max_attempts: usize, seed: u64) -> Self {
         RandomLinesStrategy { max_attempts, seed }
     }
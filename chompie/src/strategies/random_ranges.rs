@@ -18,6 +18,7 @@ impl RandomRangesStrategy {
         }
     }
 
+    #[allow(dead_code)]
     pub fn with_seed(max_attempts: usize, seed: u64) -> Self {
         RandomRangesStrategy { max_attempts, seed }
     }
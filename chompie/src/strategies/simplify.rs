@@ -0,0 +1,261 @@
+use crate::file_manager::FileState;
+use crate::strategy::{Chomp, ChompRange, LineEdit, Strategy};
+use crate::tokens::{tokenize, Token, TokenKind};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+/// Which kind of rewrite a simplify strategy proposes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Simplification {
+    /// Numbers become 0 or 1, strings become empty
+    Literals,
+    /// `if cond { a } else { b }` becomes `a` or `b`
+    Branches,
+    /// Call arguments are dropped one at a time
+    Arguments,
+}
+
+/// Simplify strategy: shrinks what stays instead of removing it, C-Reduce style
+///
+/// Rewrites are found with the token-based tokenizer, so they work on every supported
+/// language, and stay within a single surviving line.
+pub struct SimplifyStrategy {
+    simplification: Simplification,
+}
+
+impl SimplifyStrategy {
+    pub fn new(simplification: Simplification) -> Self {
+        SimplifyStrategy { simplification }
+    }
+
+    /// Every simplification in the family
+    pub fn all() -> Vec<SimplifyStrategy> {
+        [Simplification::Literals, Simplification::Branches, Simplification::Arguments]
+            .into_iter()
+            .map(SimplifyStrategy::new)
+            .collect()
+    }
+
+    /// Simplified versions of a line, in order
+    fn rewrites(&self, text: &str, is_rust: bool) -> Vec<String> {
+        let tokens = tokenize(text);
+        let mut rewrites = Vec::new();
+        let mut replace = |start: usize, end: usize, with: &str| {
+            rewrites.push(format!("{}{}{}", &text[..start], with, &text[end..]));
+        };
+
+        match self.simplification {
+            Simplification::Literals => {
+                for token in &tokens {
+                    let literal = token.text(text);
+                    match token.kind {
+                        TokenKind::Number if literal != "0" && literal != "1" => {
+                            // Keep type suffixes like `u8` or `f64` so inference still works
+                            let radix = ["0x", "0o", "0b"].iter().any(|prefix| literal.starts_with(prefix));
+                            let split = literal.find(|c: char| c.is_alphabetic() || c == '_');
+                            let (digits, suffix) = match split {
+                                Some(split) if !radix => literal.split_at(split),
+                                _ => (literal, ""),
+                            };
+                            let (zero, one) = if digits.contains('.') { ("0.0", "1.0") } else { ("0", "1") };
+                            replace(token.start, token.end, &format!("{}{}", zero, suffix));
+                            replace(token.start, token.end, &format!("{}{}", one, suffix));
+                        }
+                        // Single quotes are char literals in Rust, which can't be empty
+                        TokenKind::Str if literal.len() > 2 && !(is_rust && literal.starts_with('\'')) => {
+                            let quote = &literal[..1];
+                            replace(token.start, token.end, &format!("{}{}", quote, quote));
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            Simplification::Branches => {
+                for (i, token) in tokens.iter().enumerate() {
+                    if token.text(text) != "if" {
+                        continue;
+                    }
+                    let Some(then_open) = (i + 1..tokens.len()).find(|&j| tokens[j].text(text) == "{") else {
+                        continue;
+                    };
+                    let Some(then_close) = matching(&tokens, text, then_open) else {
+                        continue;
+                    };
+                    let else_open = then_close + 2;
+                    if tokens.get(then_close + 1).map(|t| t.text(text)) != Some("else")
+                        || tokens.get(else_open).map(|t| t.text(text)) != Some("{")
+                    {
+                        continue;
+                    }
+                    let Some(else_close) = matching(&tokens, text, else_open) else {
+                        continue;
+                    };
+                    let (start, end) = (token.start, tokens[else_close].end);
+                    for (open, close) in [(then_open, then_close), (else_open, else_close)] {
+                        let branch = text[tokens[open].end..tokens[close].start].trim();
+                        replace(start, end, branch);
+                    }
+                }
+            }
+            Simplification::Arguments => {
+                for (i, token) in tokens.iter().enumerate() {
+                    // A call (or signature) is an identifier directly followed by `(`
+                    if token.text(text) != "(" || i == 0 || tokens[i - 1].kind != TokenKind::Ident {
+                        continue;
+                    }
+                    let Some(close) = matching(&tokens, text, i) else {
+                        continue;
+                    };
+                    let arguments = split_arguments(&tokens, text, i, close);
+                    for (n, &(first, last)) in arguments.iter().enumerate() {
+                        // Drop the argument with the comma after it, or before it for the last one
+                        let (start, end) = if n + 1 < arguments.len() {
+                            (tokens[first].start, tokens[arguments[n + 1].0].start)
+                        } else if n > 0 {
+                            (tokens[arguments[n - 1].1].end, tokens[last].end)
+                        } else {
+                            (tokens[first].start, tokens[last].end)
+                        };
+                        replace(start, end, "");
+                    }
+                }
+            }
+        }
+
+        rewrites.retain(|rewrite| rewrite != text);
+        rewrites
+    }
+}
+
+/// Index of the token closing the bracket opened at `open`, on the same line
+fn matching(tokens: &[Token], text: &str, open: usize) -> Option<usize> {
+    let mut depth = 0;
+    for (j, token) in tokens.iter().enumerate().skip(open) {
+        match token.text(text) {
+            "(" | "[" | "{" => depth += 1,
+            ")" | "]" | "}" => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(j);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Token index ranges (first, last) of the top-level arguments between `open` and `close`
+fn split_arguments(tokens: &[Token], text: &str, open: usize, close: usize) -> Vec<(usize, usize)> {
+    let mut arguments = Vec::new();
+    let mut depth = 0;
+    let mut first = open + 1;
+
+    for (j, token) in tokens.iter().enumerate().take(close + 1).skip(open + 1) {
+        let t = token.text(text);
+        match t {
+            "(" | "[" | "{" => depth += 1,
+            ")" | "]" | "}" if depth > 0 => depth -= 1,
+            _ if (t == "," && depth == 0) || j == close => {
+                if j > first {
+                    arguments.push((first, j - 1));
+                }
+                first = j + 1;
+            }
+            _ => {}
+        }
+    }
+
+    arguments
+}
+
+impl Strategy for SimplifyStrategy {
+    fn name(&self) -> &str {
+        match self.simplification {
+            Simplification::Literals => "simplify_literals",
+            Simplification::Branches => "simplify_branches",
+            Simplification::Arguments => "simplify_args",
+        }
+    }
+
+    fn generate_ranges(&self, _files: &HashMap<PathBuf, FileState>) -> Vec<ChompRange> {
+        // Rewrites lines rather than blanking them
+        Vec::new()
+    }
+
+    fn generate_chomps(&self, files: &HashMap<PathBuf, FileState>) -> Vec<Chomp> {
+        let mut paths: Vec<&PathBuf> = files.keys().collect();
+        paths.sort();
+
+        let mut chomps = Vec::new();
+
+        for path in paths {
+            let state = &files[path];
+            let is_rust = path.extension().is_some_and(|ext| ext == "rs");
+            for line in state.non_blank_line_indices() {
+                let text = state.line(line);
+                for replacement in self.rewrites(text, is_rust) {
+                    chomps.push(Chomp::Edit(LineEdit {
+                        file: path.clone(),
+                        line,
+                        expected: text.to_string(),
+                        replacement,
+                    }));
+                }
+            }
+        }
+
+        chomps
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rewrites(simplification: Simplification, text: &str) -> Vec<String> {
+        SimplifyStrategy::new(simplification).rewrites(text, true)
+    }
+
+    #[test]
+    fn test_simplify_literals() {
+        assert_eq!(
+            rewrites(Simplification::Literals, "let x = f(42u8, 2.5, \"hi\", 1);"),
+            vec![
+                "let x = f(0u8, 2.5, \"hi\", 1);",
+                "let x = f(1u8, 2.5, \"hi\", 1);",
+                "let x = f(42u8, 0.0, \"hi\", 1);",
+                "let x = f(42u8, 1.0, \"hi\", 1);",
+                "let x = f(42u8, 2.5, \"\", 1);",
+            ]
+        );
+        // Rust char literals can't be emptied
+        assert!(rewrites(Simplification::Literals, "let c = 'a';").is_empty());
+        // Only the bounds of a range are literals
+        assert_eq!(rewrites(Simplification::Literals, "for i in 0..10 {"), vec!["for i in 0..0 {", "for i in 0..1 {"]);
+        assert!(rewrites(Simplification::Literals, "let y = 1.max(x);").is_empty());
+    }
+
+    #[test]
+    fn test_simplify_branches() {
+        assert_eq!(
+            rewrites(Simplification::Branches, "let y = if x > 0 { f(x) } else { 0 };"),
+            vec!["let y = f(x);", "let y = 0;"]
+        );
+    }
+
+    #[test]
+    fn test_simplify_arguments() {
+        assert_eq!(
+            rewrites(Simplification::Arguments, "foo(a, g(b, c), d);"),
+            vec!["foo(g(b, c), d);", "foo(a, d);", "foo(a, g(b, c));", "foo(a, g(c), d);", "foo(a, g(b), d);"]
+        );
+        assert_eq!(rewrites(Simplification::Arguments, "foo(a);"), vec!["foo();"]);
+    }
+
+    #[test]
+    fn test_simplify_names() {
+        let names: Vec<String> = SimplifyStrategy::all().iter().map(|s| s.name().to_string()).collect();
+        assert_eq!(names, vec!["simplify_literals", "simplify_branches", "simplify_args"]);
+    }
+}
@@ -0,0 +1,214 @@
+use crate::block_tree::Block;
+use crate::file_manager::FileState;
+use crate::strategy::{Chomp, ChompRange, SpanReplacement, Strategy};
+use crate::tokens::tokenize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Stub strategy: replaces function bodies with stubs instead of deleting them
+///
+/// Deleting the body of a function with a return type breaks compilation, but a stub
+/// keeps the signature valid: `todo!()` / `unimplemented!()` in Rust, `pass` /
+/// `raise NotImplementedError` in Python, an empty body in JS/TS and `panic("")` in Go.
+/// Function bodies are found with the block tree, so nested functions and methods count.
+pub struct StubStrategy;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Language {
+    Rust,
+    Python,
+    JavaScript,
+    Go,
+}
+
+impl Language {
+    fn for_path(path: &Path) -> Option<Language> {
+        match path.extension()?.to_str()? {
+            "rs" => Some(Language::Rust),
+            "py" => Some(Language::Python),
+            "js" | "ts" => Some(Language::JavaScript),
+            "go" => Some(Language::Go),
+            _ => None,
+        }
+    }
+
+    /// Bodies to try, most preferred first
+    fn stubs(self) -> &'static [&'static str] {
+        match self {
+            Language::Rust => &["todo!()", "unimplemented!()"],
+            Language::Python => &["pass", "raise NotImplementedError"],
+            Language::JavaScript => &[""],
+            Language::Go => &["panic(\"\")"],
+        }
+    }
+
+    fn is_function_header(self, line: &str) -> bool {
+        let trimmed = line.trim();
+        let tokens = tokenize(trimmed);
+        let words: Vec<&str> = tokens.iter().map(|t| &trimmed[t.start..t.end]).collect();
+
+        match self {
+            Language::Rust => words.contains(&"fn") && trimmed.ends_with('{'),
+            Language::Go => words.first() == Some(&"func") && trimmed.ends_with('{'),
+            Language::Python => {
+                let words = if words.first() == Some(&"async") { &words[1..] } else { &words[..] };
+                words.first() == Some(&"def") && trimmed.ends_with(':')
+            }
+            Language::JavaScript => {
+                const CONTROL: [&str; 9] = ["if", "for", "while", "switch", "catch", "else", "do", "try", "class"];
+                if !trimmed.ends_with('{') || words.first().is_some_and(|w| CONTROL.contains(w)) {
+                    return false;
+                }
+                let modifiers = ["async", "static", "get", "set", "public", "private", "protected"];
+                let rest: Vec<&str> = words.iter().copied().skip_while(|w| modifiers.contains(w)).collect();
+                // function declarations, arrow functions, and methods like `name(args) {`
+                words.contains(&"function")
+                    || trimmed.ends_with("=> {")
+                    || (rest.len() > 1 && rest[1] == "(" && rest[0].chars().all(|c| c.is_alphanumeric() || c == '_'))
+            }
+        }
+    }
+
+    /// Lines [start, end) holding the body of a function block
+    fn body(self, block: &Block, state: &FileState) -> Option<(usize, usize)> {
+        let (start, end) = match self {
+            Language::Python => (block.start_line + 1, block.end_line),
+            _ => {
+                // The closing brace must be alone at the start of the last line
+                let closing = block.end_line.checked_sub(1)?;
+                if closing <= block.start_line || !state.line(closing).trim_start().starts_with('}') {
+                    return None;
+                }
+                (block.start_line + 1, closing)
+            }
+        };
+        (start < end).then_some((start, end))
+    }
+}
+
+fn collect_stubs(
+    chomps: &mut Vec<Chomp>,
+    language: Language,
+    state: &FileState,
+    blocks: &[Block],
+) {
+    for block in blocks {
+        if language.is_function_header(state.line(block.start_line)) {
+            if let Some((start, end)) = language.body(block, state) {
+                push_stubs(chomps, language, state, start, end);
+            }
+        }
+        collect_stubs(chomps, language, state, &block.children);
+    }
+}
+
+fn push_stubs(chomps: &mut Vec<Chomp>, language: Language, state: &FileState, start: usize, end: usize) {
+    let expected = state.span_text(start, end);
+    let indent: String = (start..end)
+        .map(|i| state.line(i))
+        .find(|line| !line.trim().is_empty())
+        .map(|line| line.chars().take_while(|c| c.is_whitespace()).collect())
+        .unwrap_or_default();
+
+    for stub in language.stubs() {
+        // Already stubbed
+        if expected.trim() == *stub {
+            return;
+        }
+        let text = if stub.is_empty() { String::new() } else { format!("{}{}", indent, stub) };
+        chomps.push(Chomp::Replace(SpanReplacement {
+            file: state.path.clone(),
+            start_line: start,
+            end_line: end,
+            expected: expected.clone(),
+            text,
+        }));
+    }
+}
+
+impl Strategy for StubStrategy {
+    fn name(&self) -> &str {
+        "stubs"
+    }
+
+    fn generate_ranges(&self, _files: &HashMap<PathBuf, FileState>) -> Vec<ChompRange> {
+        // Replaces bodies rather than blanking them
+        Vec::new()
+    }
+
+    fn generate_chomps(&self, files: &HashMap<PathBuf, FileState>) -> Vec<Chomp> {
+        let mut paths: Vec<&PathBuf> = files.keys().collect();
+        paths.sort();
+
+        let mut chomps = Vec::new();
+
+        for path in paths {
+            let state = &files[path];
+            let Some(language) = Language::for_path(path) else {
+                continue;
+            };
+            if state.non_blank_lines() == 0 {
+                continue;
+            }
+            let tree = Block::from_file(state);
+            collect_stubs(&mut chomps, language, state, &tree.children);
+        }
+
+        chomps
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stubs(name: &str, content: &str) -> Vec<(usize, usize, String)> {
+        let path = PathBuf::from(name);
+        let mut files = HashMap::new();
+        files.insert(path.clone(), FileState::new(path, content.to_string()));
+        StubStrategy
+            .generate_chomps(&files)
+            .into_iter()
+            .map(|chomp| match chomp {
+                Chomp::Replace(span) => (span.start_line, span.end_line, span.text),
+                other => panic!("unexpected chomp: {}", other),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_rust_stubs() {
+        let content = "impl S {\n    fn f(&self) -> i32 {\n        let x = 1;\n        x\n    }\n}";
+        assert_eq!(StubStrategy.name(), "stubs");
+        assert_eq!(
+            stubs("lib.rs", content),
+            vec![
+                (2, 4, "        todo!()".to_string()),
+                (2, 4, "        unimplemented!()".to_string()),
+            ]
+        );
+        // Already stubbed bodies are left alone
+        assert!(stubs("lib.rs", "fn f() -> i32 {\n    todo!()\n}").is_empty());
+    }
+
+    #[test]
+    fn test_python_stubs() {
+        let content = "class A:\n    def f(self):\n        return 1\n\nprint(A().f())";
+        assert_eq!(
+            stubs("main.py", content),
+            vec![
+                (2, 3, "        pass".to_string()),
+                (2, 3, "        raise NotImplementedError".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_js_and_go_stubs() {
+        let js = "function f(a) {\n  return a;\n}\nif (x) {\n  y();\n}";
+        assert_eq!(stubs("app.js", js), vec![(1, 2, String::new())]);
+
+        let go = "func f() int {\n\treturn 1\n}";
+        assert_eq!(stubs("main.go", go), vec![(1, 2, "\tpanic(\"\")".to_string())]);
+    }
+}
@@ -0,0 +1,56 @@
+use crate::file_manager::FileState;
+use crate::strategy::{ChompRange, Strategy};
+use crate::symbol_index::SymbolIndex;
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+/// Symbols strategy: removes definitions in dependency order using the symbol index
+///
+/// Definitions nothing references go first, largest first within a layer, followed by the
+/// ones only those referenced. Later rounds rebuild the index, so the order keeps following
+/// the graph as it shrinks.
+pub struct SymbolsStrategy;
+
+impl Strategy for SymbolsStrategy {
+    fn name(&self) -> &str {
+        "symbols"
+    }
+
+    fn generate_ranges(&self, files: &HashMap<PathBuf, FileState>) -> Vec<ChompRange> {
+        let index = SymbolIndex::build(files);
+        let mut ranges = Vec::new();
+
+        for mut layer in index.removal_layers() {
+            layer.sort_by_key(|d| std::cmp::Reverse(d.end_line - d.start_line));
+            ranges.extend(layer.into_iter().map(|definition| ChompRange {
+                file: definition.file.clone(),
+                start_line: definition.start_line,
+                end_line: definition.end_line,
+            }));
+        }
+
+        ranges
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_symbols_strategy() {
+        let path = PathBuf::from("main.py");
+        let content = "def a():\n    return 1\n\ndef b():\n    c()\n    c()\n\ndef c():\n    pass\n\nprint(a())";
+        let mut files = HashMap::new();
+        files.insert(path.clone(), FileState::new(path, content.to_string()));
+
+        let ranges: Vec<(usize, usize)> = SymbolsStrategy
+            .generate_ranges(&files)
+            .iter()
+            .map(|r| (r.start_line, r.end_line))
+            .collect();
+        assert_eq!(SymbolsStrategy.name(), "symbols");
+        // `b` is unreferenced, and `c` is only referenced by `b`; `a` is used
+        assert_eq!(ranges, vec![(3, 6), (7, 9)]);
+    }
+}
@@ -0,0 +1,143 @@
+use crate::file_manager::FileState;
+use crate::strategy::{Chomp, ChompRange, LineEdit, Strategy};
+use crate::tokens::tokenize;
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+/// How finely a surviving line is cut up
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Granularity {
+    Tokens,
+    Chars,
+}
+
+/// Token strategy: removes tokens (or single characters) from lines that survived
+/// line-level chomping, C-Reduce style
+///
+/// For each line it first tries removing pairs of adjacent tokens, then single tokens,
+/// along with the whitespace after them. The character granularity tries removing every
+/// non-whitespace character instead, which is slow but finds the last few bytes.
+pub struct TokenStrategy {
+    granularity: Granularity,
+}
+
+impl TokenStrategy {
+    pub fn new(granularity: Granularity) -> Self {
+        TokenStrategy { granularity }
+    }
+
+    /// Byte spans to try removing from a line, in order
+    fn spans(&self, text: &str) -> Vec<(usize, usize)> {
+        match self.granularity {
+            Granularity::Tokens => {
+                let tokens = tokenize(text);
+                // Removing the only token is the same as blanking the line
+                if tokens.len() < 2 {
+                    return Vec::new();
+                }
+                let mut spans = Vec::new();
+                for size in [2, 1] {
+                    for window in tokens.windows(size) {
+                        let start = window[0].start;
+                        let end = window[size - 1].end;
+                        // Take the following whitespace along, or the preceding at the end
+                        let trailing = text[end..].len() - text[end..].trim_start().len();
+                        if trailing > 0 {
+                            spans.push((start, end + trailing));
+                        } else {
+                            let leading = text[..start].len() - text[..start].trim_end().len();
+                            spans.push((start - leading, end));
+                        }
+                    }
+                }
+                spans
+            }
+            Granularity::Chars => text
+                .char_indices()
+                .filter(|(_, c)| !c.is_whitespace())
+                .map(|(i, c)| (i, i + c.len_utf8()))
+                .collect(),
+        }
+    }
+}
+
+impl Strategy for TokenStrategy {
+    fn name(&self) -> &str {
+        match self.granularity {
+            Granularity::Tokens => "tokens",
+            Granularity::Chars => "chars",
+        }
+    }
+
+    fn generate_ranges(&self, _files: &HashMap<PathBuf, FileState>) -> Vec<ChompRange> {
+        // Works below line level only
+        Vec::new()
+    }
+
+    fn generate_chomps(&self, files: &HashMap<PathBuf, FileState>) -> Vec<Chomp> {
+        let mut paths: Vec<&PathBuf> = files.keys().collect();
+        paths.sort();
+
+        let mut chomps = Vec::new();
+
+        for path in paths {
+            let state = &files[path];
+            for line in state.non_blank_line_indices() {
+                let text = state.line(line);
+                for (start, end) in self.spans(text) {
+                    chomps.push(Chomp::Edit(LineEdit {
+                        file: path.clone(),
+                        line,
+                        expected: text.to_string(),
+                        replacement: format!("{}{}", &text[..start], &text[end..]),
+                    }));
+                }
+            }
+        }
+
+        chomps
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn replacements(strategy: &TokenStrategy, content: &str) -> Vec<String> {
+        let path = PathBuf::from("test.rs");
+        let mut files = HashMap::new();
+        files.insert(path.clone(), FileState::new(path, content.to_string()));
+        strategy
+            .generate_chomps(&files)
+            .into_iter()
+            .map(|chomp| match chomp {
+                Chomp::Edit(edit) => edit.replacement,
+                other => panic!("unexpected chomp: {}", other),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_token_strategy() {
+        let strategy = TokenStrategy::new(Granularity::Tokens);
+        assert_eq!(strategy.name(), "tokens");
+
+        let edits = replacements(&strategy, "f(a, b)\nsingle");
+        assert_eq!(
+            edits,
+            vec![
+                // Pairs
+                "a, b)", "f, b)", "f(b)", "f(a)", "f(a,",
+                // Singles
+                "(a, b)", "fa, b)", "f(, b)", "f(ab)", "f(a,)", "f(a, b",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_char_strategy() {
+        let strategy = TokenStrategy::new(Granularity::Chars);
+        assert_eq!(strategy.name(), "chars");
+        assert_eq!(replacements(&strategy, "a b"), vec![" b", "a "]);
+    }
+}
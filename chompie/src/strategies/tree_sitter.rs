@@ -0,0 +1,157 @@
+use crate::file_manager::FileState;
+use crate::strategy::{Chomp, ChompRange, LineEdit, SpanReplacement, Strategy};
+use crate::syntax;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use tree_sitter::Tree;
+
+/// Tree-sitter strategy: chomps real syntax nodes instead of arbitrary lines
+///
+/// Each file with a grammar is parsed and every node that covers whole lines becomes a
+/// candidate, outermost first: items, then statements, match arms, struct fields, and
+/// arguments or expressions on their own lines. Nodes that share a line with other code,
+/// like two arguments in one call, follow as edits that cut just the node out. Candidates
+/// are re-parsed before the command runs and rejected if they no longer parse, so a chomp
+/// that breaks the syntax never costs a run, and the files are parsed again after every
+/// kept chomp. Files that don't parse cleanly to begin with are left alone.
+pub struct TreeSitterStrategy;
+
+impl TreeSitterStrategy {
+    /// Files that parse cleanly, with their content and tree, in path order
+    fn parsed(files: &HashMap<PathBuf, FileState>) -> Vec<(&PathBuf, String, Tree)> {
+        let mut paths: Vec<&PathBuf> = files.keys().collect();
+        paths.sort();
+
+        paths
+            .into_iter()
+            .filter(|path| files[*path].non_blank_lines() > 0)
+            .filter_map(|path| {
+                let content = files[path].current_content();
+                let tree = syntax::parse(path, &content)?;
+                (!tree.root_node().has_error()).then_some((path, content, tree))
+            })
+            .collect()
+    }
+}
+
+impl Strategy for TreeSitterStrategy {
+    fn name(&self) -> &str {
+        "tree_sitter"
+    }
+
+    fn generate_ranges(&self, files: &HashMap<PathBuf, FileState>) -> Vec<ChompRange> {
+        let mut ranges = Vec::new();
+
+        for (path, content, tree) in Self::parsed(files) {
+            let lines: Vec<&str> = content.split('\n').collect();
+            for (start_line, end_line) in syntax::line_aligned_spans(&tree, &lines) {
+                ranges.push(ChompRange {
+                    file: path.clone(),
+                    start_line,
+                    end_line,
+                });
+            }
+        }
+
+        ranges
+    }
+
+    fn generate_chomps(&self, files: &HashMap<PathBuf, FileState>) -> Vec<Chomp> {
+        let mut chomps: Vec<Chomp> = self.generate_ranges(files).into_iter().map(Chomp::Range).collect();
+
+        for (path, content, tree) in Self::parsed(files) {
+            let lines: Vec<&str> = content.split('\n').collect();
+            for (start, end) in syntax::inline_spans(&tree, &lines) {
+                let text = format!("{}{}", &lines[start.row][..start.column], &lines[end.row][end.column..]);
+                if start.row == end.row {
+                    chomps.push(Chomp::Edit(LineEdit {
+                        file: path.clone(),
+                        line: start.row,
+                        expected: lines[start.row].to_string(),
+                        replacement: text,
+                    }));
+                } else {
+                    chomps.push(Chomp::Replace(SpanReplacement {
+                        file: path.clone(),
+                        start_line: start.row,
+                        end_line: end.row + 1,
+                        expected: lines[start.row..=end.row].join("\n"),
+                        text,
+                    }));
+                }
+            }
+        }
+
+        chomps
+    }
+
+    fn accepts(&self, files: &HashMap<PathBuf, FileState>, changed: &[PathBuf]) -> bool {
+        changed
+            .iter()
+            .filter_map(|path| files.get(path))
+            .filter(|state| !state.deleted)
+            .all(|state| !syntax::has_errors(&state.path, &state.current_content()))
+    }
+
+    fn regenerates(&self) -> bool {
+        // A kept chomp moves the nodes after it, so candidates come from a fresh parse
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tree_sitter_strategy() {
+        let content = "fn a() {\n    one();\n}\n\nfn b() {}";
+        let path = PathBuf::from("main.rs");
+        let mut files = HashMap::new();
+        files.insert(path.clone(), FileState::new(path.clone(), content.to_string()));
+
+        let strategy = TreeSitterStrategy;
+        let ranges = strategy.generate_ranges(&files);
+        assert_eq!(strategy.name(), "tree_sitter");
+
+        let spans: Vec<_> = ranges.iter().map(|r| (r.start_line, r.end_line)).collect();
+        assert_eq!(spans, vec![(0, 3), (4, 5), (1, 2)]);
+    }
+
+    #[test]
+    fn test_tree_sitter_drops_arguments_on_one_line() {
+        let content = "fn main() {\n    f(a, b);\n}";
+        let path = PathBuf::from("main.rs");
+        let mut files = HashMap::new();
+        files.insert(path.clone(), FileState::new(path.clone(), content.to_string()));
+
+        let strategy = TreeSitterStrategy;
+        let edits: Vec<String> = strategy
+            .generate_chomps(&files)
+            .into_iter()
+            .filter_map(|chomp| match chomp {
+                Chomp::Edit(edit) if edit.line == 1 => Some(edit.replacement),
+                _ => None,
+            })
+            .collect();
+
+        assert!(edits.contains(&"    f(b);".to_string()));
+        assert!(edits.contains(&"    f(a);".to_string()));
+        assert!(strategy.regenerates());
+    }
+
+    #[test]
+    fn test_tree_sitter_rejects_parse_errors() {
+        let content = "fn a() {\n    one();\n}";
+        let path = PathBuf::from("main.rs");
+        let mut files = HashMap::new();
+        files.insert(path.clone(), FileState::new(path.clone(), content.to_string()));
+
+        let strategy = TreeSitterStrategy;
+        assert!(strategy.accepts(&files, std::slice::from_ref(&path)));
+
+        // Blanking the closing brace leaves an unclosed function
+        files.get_mut(&path).unwrap().blank_lines(&[2]);
+        assert!(!strategy.accepts(&files, std::slice::from_ref(&path)));
+    }
+}
@@ -1,13 +1,90 @@
+use crate::file_manager::FileState;
+use std::collections::HashMap;
+use std::fmt;
 use std::path::PathBuf;
 
 /// Represents a range of lines to attempt chomping in a file
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct ChompRange {
     pub file: PathBuf,
     pub start_line: usize,
     pub end_line: usize,
 }
 
+/// Replace the text of one line, below line level
+/// Only applies while the line still reads `expected`, so stale edits are skipped
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct LineEdit {
+    pub file: PathBuf,
+    pub line: usize,
+    pub expected: String,
+    pub replacement: String,
+}
+
+/// Replace a span of lines with new text, e.g. a function body with a stub
+/// Only applies while the span still reads `expected`, so stale replacements are skipped
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SpanReplacement {
+    pub file: PathBuf,
+    pub start_line: usize,
+    /// Exclusive
+    pub end_line: usize,
+    pub expected: String,
+    pub text: String,
+}
+
+/// A single chomp attempt. Everything in a chomp is kept or rolled back together.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Chomp {
+    /// Blank a range of lines in one file
+    Range(ChompRange),
+    /// Delete a group of files from disk
+    DeleteFiles(Vec<PathBuf>),
+    /// Rewrite part of a line
+    Edit(LineEdit),
+    /// Rewrite a span of lines
+    Replace(SpanReplacement),
+    /// Several chomps that only make sense together, e.g. inlining a module into its
+    /// parent and deleting its file. Skipped as a whole if any part is stale.
+    Compound(Vec<Chomp>),
+}
+
+impl fmt::Display for Chomp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Chomp::Range(range) => write!(
+                f,
+                "{:?} lines {}-{} ({} lines)",
+                range.file,
+                range.start_line,
+                range.end_line,
+                range.end_line - range.start_line
+            ),
+            Chomp::DeleteFiles(paths) => write!(f, "delete {} file(s): {:?}", paths.len(), paths),
+            Chomp::Edit(edit) => write!(
+                f,
+                "{:?} line {}: {:?} -> {:?}",
+                edit.file, edit.line, edit.expected, edit.replacement
+            ),
+            Chomp::Replace(span) => write!(
+                f,
+                "{:?} lines {}-{} -> {:?}",
+                span.file, span.start_line, span.end_line, span.text
+            ),
+            Chomp::Compound(parts) => {
+                write!(f, "[")?;
+                for (i, part) in parts.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, "; ")?;
+                    }
+                    write!(f, "{}", part)?;
+                }
+                write!(f, "]")
+            }
+        }
+    }
+}
+
 /// A strategy for generating chomp attempts
 pub trait Strategy {
     /// Name of this strategy for display purposes
@@ -15,5 +92,26 @@ pub trait Strategy {
 
     /// Generate chomp ranges to try
     /// Returns a list of ranges to attempt, in order
-    fn generate_ranges(&self, files: &std::collections::HashMap<PathBuf, crate::file_manager::FileState>) -> Vec<ChompRange>;
+    fn generate_ranges(&self, files: &HashMap<PathBuf, FileState>) -> Vec<ChompRange>;
+
+    /// Generate chomps to try, in order
+    /// Defaults to blanking each range from `generate_ranges`; strategies that do more
+    /// than blank lines (e.g. deleting files) override this
+    fn generate_chomps(&self, files: &HashMap<PathBuf, FileState>) -> Vec<Chomp> {
+        self.generate_ranges(files).into_iter().map(Chomp::Range).collect()
+    }
+
+    /// Whether a chomp from this strategy is worth running the command for
+    /// Called with the files after the chomp is applied, before anything is written.
+    /// Returning false rolls the chomp back without running the command.
+    fn accepts(&self, _files: &HashMap<PathBuf, FileState>, _changed: &[PathBuf]) -> bool {
+        true
+    }
+
+    /// Whether the chomps still to try should be generated again after each kept chomp
+    /// For strategies whose candidates come from an analysis of the files, e.g. a parse,
+    /// that a kept chomp leaves stale. Candidates already tried aren't tried again.
+    fn regenerates(&self) -> bool {
+        false
+    }
 }
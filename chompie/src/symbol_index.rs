@@ -0,0 +1,248 @@
+use crate::block_tree::Block;
+use crate::file_manager::FileState;
+use crate::tokens::{tokenize, Token, TokenKind};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+/// Keywords that introduce a named definition in at least one supported language
+const DEFINITION_KEYWORDS: [&str; 14] = [
+    "fn", "struct", "enum", "trait", "union", "type", "const", "static", "mod", "def", "class", "function",
+    "func", "interface",
+];
+
+/// Keywords that look like calls in C but aren't function definitions
+const C_CONTROL: [&str; 6] = ["if", "while", "for", "switch", "return", "sizeof"];
+
+/// A named definition and the lines it spans, attributes and doc comments included
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Definition {
+    pub name: String,
+    pub file: PathBuf,
+    pub start_line: usize,
+    /// Exclusive
+    pub end_line: usize,
+}
+
+impl Definition {
+    fn contains(&self, file: &Path, line: usize) -> bool {
+        self.file == file && (self.start_line..self.end_line).contains(&line)
+    }
+}
+
+/// Lightweight cross-file index of definitions and the identifiers that reference them
+///
+/// Definitions are found per line from keywords (`fn`, `def`, `class`, `func`, ...) plus a
+/// top-level `name(` heuristic for C/C++, and their extent comes from the block tree. A
+/// reference is any identifier token with a definition's name, so the index over-approximates
+/// uses, which errs on the side of keeping code.
+pub struct SymbolIndex {
+    pub definitions: Vec<Definition>,
+    references: HashMap<String, Vec<(PathBuf, usize)>>,
+}
+
+/// Name defined on a line, if any
+fn defined_name(line: &str, is_c: bool) -> Option<String> {
+    if is_c {
+        return c_defined_name(line);
+    }
+    let tokens = tokenize(line);
+    let text = |i: usize| tokens[i].text(line);
+
+    for (i, token) in tokens.iter().enumerate() {
+        if token.kind != TokenKind::Ident || !DEFINITION_KEYWORDS.contains(&text(i)) {
+            continue;
+        }
+        // A field or method called `type` isn't a definition
+        if i > 0 && text(i - 1) == "." {
+            return None;
+        }
+        let mut next = i + 1;
+        // Go methods: `func (r *T) Name(`
+        if text(i) == "func" && next < tokens.len() && text(next) == "(" {
+            next = (next..tokens.len()).find(|&j| text(j) == ")")? + 1;
+        }
+        return tokens
+            .get(next)
+            .filter(|t| t.kind == TokenKind::Ident)
+            .map(|t| t.text(line).to_string());
+    }
+
+    None
+}
+
+/// Name defined on a C/C++ line: a top-level `name(` that isn't a preprocessor line or an
+/// assignment, or a `struct`/`enum`/`union` with a body
+fn c_defined_name(line: &str) -> Option<String> {
+    let tokens = tokenize(line);
+    let text = |i: usize| tokens[i].text(line);
+    if line.starts_with(char::is_whitespace) || line.starts_with('#') {
+        return None;
+    }
+
+    if let Some(paren) = tokens.iter().position(|t| t.text(line) == "(") {
+        let is_function = paren > 0
+            && tokens[..paren].iter().all(|t| t.text(line) != "=")
+            && tokens[paren - 1].kind == TokenKind::Ident
+            && !C_CONTROL.contains(&text(paren - 1));
+        return is_function.then(|| text(paren - 1).to_string());
+    }
+    let keyword = (0..tokens.len()).find(|&i| matches!(text(i), "struct" | "enum" | "union"))?;
+    let name = tokens.get(keyword + 1).filter(|t| t.kind == TokenKind::Ident)?;
+    (tokens.get(keyword + 2).map(|t| t.text(line)) == Some("{")).then(|| name.text(line).to_string())
+}
+
+/// Tokens of a line up to its comment, if any
+fn code_tokens(line: &str, hash_comments: bool) -> Vec<Token> {
+    let mut tokens = tokenize(line);
+    let comment = (0..tokens.len()).find(|&i| {
+        let text = tokens[i].text(line);
+        let next = tokens.get(i + 1).filter(|next| next.start == tokens[i].end).map(|next| next.text(line));
+        (text == "/" && next == Some("/")) || (hash_comments && text == "#")
+    });
+    if let Some(comment) = comment {
+        tokens.truncate(comment);
+    }
+    tokens
+}
+
+/// Where each block starts and ends, at every depth
+fn block_extents(block: &Block, extents: &mut HashMap<usize, usize>) {
+    for child in &block.children {
+        extents.entry(child.start_line).or_insert(child.end_line);
+        block_extents(child, extents);
+    }
+}
+
+/// Whether a line belongs to the definition below it
+fn is_preamble(line: &str) -> bool {
+    let trimmed = line.trim_start();
+    trimmed.starts_with("#[") || trimmed.starts_with("//") || trimmed.starts_with('@')
+}
+
+impl SymbolIndex {
+    pub fn build(files: &HashMap<PathBuf, FileState>) -> SymbolIndex {
+        let mut paths: Vec<&PathBuf> = files.keys().filter(|path| !files[*path].deleted).collect();
+        paths.sort();
+
+        let mut definitions = Vec::new();
+        for path in &paths {
+            let state = &files[*path];
+            let is_c = path.extension().is_some_and(|ext| ext == "c" || ext == "cpp" || ext == "h");
+            let mut extents = HashMap::new();
+            block_extents(&Block::from_file(state), &mut extents);
+
+            for line in state.non_blank_line_indices() {
+                let Some(name) = defined_name(state.line(line), is_c) else {
+                    continue;
+                };
+                // Entry points and dunder methods are used without being named
+                if name == "main" || (name.starts_with("__") && name.ends_with("__")) {
+                    continue;
+                }
+                let mut start_line = line;
+                while start_line > 0 && is_preamble(state.line(start_line - 1)) {
+                    start_line -= 1;
+                }
+                definitions.push(Definition {
+                    name,
+                    file: (*path).clone(),
+                    start_line,
+                    end_line: extents.get(&line).copied().unwrap_or(line + 1),
+                });
+            }
+        }
+
+        let names: HashSet<&str> = definitions.iter().map(|d| d.name.as_str()).collect();
+        let mut references: HashMap<String, Vec<(PathBuf, usize)>> = HashMap::new();
+        for path in &paths {
+            let state = &files[*path];
+            let hash_comments = path.extension().is_some_and(|ext| ext == "py" || ext == "rb");
+            for line in state.non_blank_line_indices() {
+                let text = state.line(line);
+                for token in code_tokens(text, hash_comments) {
+                    let word = token.text(text);
+                    if token.kind == TokenKind::Ident && names.contains(word) {
+                        references.entry(word.to_string()).or_default().push(((*path).clone(), line));
+                    }
+                }
+            }
+        }
+
+        SymbolIndex { definitions, references }
+    }
+
+    /// Number of references to a definition's name from outside it and outside `removed`
+    pub fn live_references(&self, definition: &Definition, removed: &[&Definition]) -> usize {
+        self.references.get(&definition.name).map_or(0, |uses| {
+            uses.iter()
+                .filter(|(file, line)| {
+                    !definition.contains(file, *line) && !removed.iter().any(|r| r.contains(file, *line))
+                })
+                .count()
+        })
+    }
+
+    /// Definitions in the order they can be removed: first those nothing references,
+    /// then those only the first layer referenced, and so on
+    pub fn removal_layers(&self) -> Vec<Vec<&Definition>> {
+        let mut removed: Vec<&Definition> = Vec::new();
+        let mut layers = Vec::new();
+
+        loop {
+            let layer: Vec<&Definition> = self
+                .definitions
+                .iter()
+                .filter(|d| !removed.iter().any(|r| r.contains(&d.file, d.start_line)))
+                .filter(|d| self.live_references(d, &removed) == 0)
+                .collect();
+            if layer.is_empty() {
+                return layers;
+            }
+            removed.extend(layer.iter().copied());
+            layers.push(layer);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn index(entries: &[(&str, &str)]) -> SymbolIndex {
+        let files: HashMap<PathBuf, FileState> = entries
+            .iter()
+            .map(|(path, content)| (PathBuf::from(path), FileState::new(PathBuf::from(path), content.to_string())))
+            .collect();
+        SymbolIndex::build(&files)
+    }
+
+    #[test]
+    fn test_defined_name() {
+        assert_eq!(defined_name("pub(crate) fn parse(x: &str) {", false), Some("parse".to_string()));
+        assert_eq!(defined_name("    def run(self):", false), Some("run".to_string()));
+        assert_eq!(defined_name("func (s *Server) Start() error {", false), Some("Start".to_string()));
+        assert_eq!(defined_name("export function helper() {", false), Some("helper".to_string()));
+        assert_eq!(defined_name("static int count(int *xs) {", true), Some("count".to_string()));
+        assert_eq!(defined_name("    if (x) {", true), None);
+        assert_eq!(defined_name("node.type = 1;", false), None);
+    }
+
+    #[test]
+    fn test_removal_layers() {
+        let index = index(&[
+            ("src/main.rs", "fn main() {\n    used();\n}\n\nfn used() {}"),
+            (
+                "src/lib.rs",
+                "/// Only called by unused\nfn helper() -> i32 {\n    helper() + 1\n}\n\n#[inline]\nfn unused() {\n    helper();\n}",
+            ),
+        ]);
+
+        let names: Vec<Vec<(&str, usize, usize)>> = index
+            .removal_layers()
+            .iter()
+            .map(|layer| layer.iter().map(|d| (d.name.as_str(), d.start_line, d.end_line)).collect())
+            .collect();
+        // Recursion doesn't keep `helper` alive, but `unused` does until it's gone
+        assert_eq!(names, vec![vec![("unused", 5, 9)], vec![("helper", 0, 4)]]);
+    }
+}
@@ -0,0 +1,177 @@
+use std::path::Path;
+use tree_sitter::{Language, Node, Parser, Point, Tree};
+
+/// Tree-sitter grammar for a file, picked by extension
+pub fn language_for(path: &Path) -> Option<Language> {
+    let ext = path.extension()?.to_string_lossy();
+    let language = match ext.as_ref() {
+        "rs" => tree_sitter_rust::LANGUAGE,
+        "py" => tree_sitter_python::LANGUAGE,
+        "js" => tree_sitter_javascript::LANGUAGE,
+        "ts" => tree_sitter_typescript::LANGUAGE_TYPESCRIPT,
+        "java" => tree_sitter_java::LANGUAGE,
+        "c" => tree_sitter_c::LANGUAGE,
+        // Headers are shared between C and C++, and the C++ grammar accepts most C
+        "cpp" | "h" => tree_sitter_cpp::LANGUAGE,
+        "rb" => tree_sitter_ruby::LANGUAGE,
+        "go" => tree_sitter_go::LANGUAGE,
+        _ => return None,
+    };
+    Some(language.into())
+}
+
+/// Parse content, or None if there is no grammar for the file
+pub fn parse(path: &Path, content: &str) -> Option<Tree> {
+    let language = language_for(path)?;
+    let mut parser = Parser::new();
+    parser.set_language(&language).ok()?;
+    parser.parse(content, None)
+}
+
+/// Whether content fails to parse cleanly; files without a grammar never do
+pub fn has_errors(path: &Path, content: &str) -> bool {
+    parse(path, content).is_some_and(|tree| tree.root_node().has_error())
+}
+
+/// Line spans [start, end) of syntax nodes that cover whole lines, outermost first
+///
+/// A node covers whole lines when only whitespace precedes it on its first line and
+/// only whitespace, `,` or `;` follows it on its last line. That picks out items,
+/// statements, match arms, struct fields and arguments or expressions on their own lines,
+/// which can be blanked without leaving half a construct behind.
+pub fn line_aligned_spans(tree: &Tree, lines: &[&str]) -> Vec<(usize, usize)> {
+    let mut spans = Vec::new();
+    let mut level: Vec<Node> = vec![tree.root_node()];
+
+    while !level.is_empty() {
+        let mut next = Vec::new();
+        for node in level {
+            let mut cursor = node.walk();
+            for child in node.named_children(&mut cursor) {
+                if let Some(span) = aligned_span(&child, lines) {
+                    if !spans.contains(&span) {
+                        spans.push(span);
+                    }
+                }
+                next.push(child);
+            }
+        }
+        level = next;
+    }
+
+    spans
+}
+
+/// Spans (start, end) of syntax nodes that share their lines with other code, outermost first
+///
+/// These are what `line_aligned_spans` can't reach: two arguments, struct fields or
+/// statements on one line, or an expression inside a larger one. A node in a
+/// comma-separated list comes with the comma after it, or before it for the last one,
+/// so dropping it leaves the list well formed. Leaves outside lists, like a lone
+/// identifier, are left out since removing one alone never parses.
+pub fn inline_spans(tree: &Tree, lines: &[&str]) -> Vec<(Point, Point)> {
+    let mut spans = Vec::new();
+    let mut level: Vec<Node> = vec![tree.root_node()];
+
+    while !level.is_empty() {
+        let mut next = Vec::new();
+        for node in level {
+            let mut cursor = node.walk();
+            for child in node.named_children(&mut cursor) {
+                next.push(child);
+                if aligned_span(&child, lines).is_some() {
+                    continue;
+                }
+
+                let is_comma = |sibling: Option<Node>| sibling.is_some_and(|sibling| sibling.kind() == ",");
+                let span = if is_comma(child.next_sibling()) {
+                    // Take the comma and the space after it
+                    let comma = child.next_sibling().unwrap().end_position();
+                    let rest = lines.get(comma.row).and_then(|line| line.get(comma.column..)).unwrap_or("");
+                    let spaces = rest.len() - rest.trim_start_matches([' ', '\t']).len();
+                    (child.start_position(), Point { column: comma.column + spaces, ..comma })
+                } else if is_comma(child.prev_sibling()) {
+                    (child.prev_sibling().unwrap().start_position(), child.end_position())
+                } else if child.named_child_count() > 0 {
+                    (child.start_position(), child.end_position())
+                } else {
+                    continue;
+                };
+                if !spans.contains(&span) {
+                    spans.push(span);
+                }
+            }
+        }
+        level = next;
+    }
+
+    spans
+}
+
+fn aligned_span(node: &Node, lines: &[&str]) -> Option<(usize, usize)> {
+    let start = node.start_position();
+    let end = node.end_position();
+    let first = lines.get(start.row)?;
+    let last = lines.get(end.row)?;
+
+    let before = first.get(..start.column)?;
+    let after = last.get(end.column..)?;
+    if !before.trim().is_empty() || !after.trim().trim_matches([',', ';']).is_empty() {
+        return None;
+    }
+
+    Some((start.row, end.row + 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_has_errors() {
+        let path = Path::new("main.rs");
+        assert!(!has_errors(path, "fn main() {\n    let x = 1;\n}\n"));
+        assert!(has_errors(path, "fn main() {\n    let x = 1;\n\n"));
+        assert!(!has_errors(Path::new("notes.txt"), "fn main() {"));
+    }
+
+    #[test]
+    fn test_line_aligned_spans() {
+        let content = "struct S {\n    a: i32,\n    b: i32,\n}\n\nfn main() {\n    let x = 1; let y = 2;\n    foo(\n        x,\n        y,\n    );\n}";
+        let lines: Vec<&str> = content.lines().collect();
+        let tree = parse(Path::new("main.rs"), content).unwrap();
+        let spans = line_aligned_spans(&tree, &lines);
+
+        // Items first
+        assert_eq!(spans[0], (0, 4));
+        assert_eq!(spans[1], (5, 12));
+        // Struct fields and arguments on their own lines
+        assert!(spans.contains(&(1, 2)));
+        assert!(spans.contains(&(2, 3)));
+        assert!(spans.contains(&(8, 9)));
+        assert!(spans.contains(&(7, 11)));
+        // Two statements sharing a line are not line-aligned on their own
+        assert!(!spans.contains(&(6, 7)));
+    }
+
+    #[test]
+    fn test_inline_spans() {
+        let content = "fn main() {\n    let x = 1; let y = 2;\n    foo(x, y);\n}";
+        let lines: Vec<&str> = content.lines().collect();
+        let tree = parse(Path::new("main.rs"), content).unwrap();
+        let texts: Vec<String> = inline_spans(&tree, &lines)
+            .into_iter()
+            .filter(|(start, end)| start.row == end.row)
+            .map(|(start, end)| lines[start.row][start.column..end.column].to_string())
+            .collect();
+
+        // Statements sharing a line, and each argument with its comma
+        assert!(texts.contains(&"let x = 1;".to_string()));
+        assert!(texts.contains(&"let y = 2;".to_string()));
+        assert!(texts.contains(&"x, ".to_string()));
+        assert!(texts.contains(&", y".to_string()));
+        // Whole lines are left to line_aligned_spans, and lone leaves aren't worth a try
+        assert!(!texts.contains(&"foo(x, y)".to_string()));
+        assert!(!texts.contains(&"foo".to_string()));
+    }
+}
@@ -0,0 +1,45 @@
+use anyhow::Result;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The current time as `YYYYMMDD-HHMMSS` in UTC, used to name branches, worktrees and sessions
+pub fn now() -> Result<String> {
+    Ok(format(SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs()))
+}
+
+/// `YYYYMMDD-HHMMSS` in UTC
+pub fn format(unix_seconds: u64) -> String {
+    let days = (unix_seconds / 86_400) as i64;
+    let seconds = unix_seconds % 86_400;
+
+    // Civil date from days since 1970-01-01 (Howard Hinnant's algorithm)
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{:04}{:02}{:02}-{:02}{:02}{:02}",
+        year,
+        month,
+        day,
+        seconds / 3_600,
+        seconds % 3_600 / 60,
+        seconds % 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format() {
+        assert_eq!(format(0), "19700101-000000");
+        assert_eq!(format(1_709_251_199), "20240229-235959");
+    }
+}
@@ -0,0 +1,131 @@
+/// Kind of a token found on a line
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenKind {
+    Ident,
+    Number,
+    Str,
+    Punct,
+}
+
+/// A token on a single line, as a byte span
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Token {
+    pub kind: TokenKind,
+    pub start: usize,
+    /// Exclusive
+    pub end: usize,
+}
+
+impl Token {
+    pub fn text<'a>(&self, line: &'a str) -> &'a str {
+        &line[self.start..self.end]
+    }
+}
+
+/// Split a line into identifiers, numbers, string literals and single punctuation characters
+///
+/// This is a language-agnostic approximation: `"..."` and `'...'` are strings, except
+/// that `'a` followed by anything but a quote is a Rust lifetime or label.
+pub fn tokenize(line: &str) -> Vec<Token> {
+    let chars: Vec<(usize, char)> = line.char_indices().collect();
+    let offset = |i: usize| chars.get(i).map_or(line.len(), |&(offset, _)| offset);
+    let is_ident = |c: char| c.is_alphanumeric() || c == '_';
+
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i].1;
+        let start = i;
+
+        let kind = if c.is_whitespace() {
+            i += 1;
+            continue;
+        } else if c.is_alphabetic() || c == '_' {
+            while i < chars.len() && is_ident(chars[i].1) {
+                i += 1;
+            }
+            TokenKind::Ident
+        } else if c.is_ascii_digit() {
+            // One decimal point, followed by a digit: `0..10`, `1.max(x)` and the tuple
+            // fields of `t.0.1` split at theirs
+            let mut point = i > 0 && chars[i - 1].1 == '.';
+            while i < chars.len() {
+                let c = chars[i].1;
+                let fraction = c == '.' && !point && chars.get(i + 1).is_some_and(|&(_, next)| next.is_ascii_digit());
+                if !is_ident(c) && !fraction {
+                    break;
+                }
+                point |= fraction;
+                i += 1;
+            }
+            TokenKind::Number
+        } else if c == '"' || c == '\'' {
+            let mut j = i + 1;
+            while j < chars.len() && is_ident(chars[j].1) {
+                j += 1;
+            }
+            if c == '\'' && j > i + 1 && chars.get(j).map(|&(_, c)| c) != Some('\'') {
+                // Lifetime or label: the quote is punctuation, the name an identifier
+                i += 1;
+                TokenKind::Punct
+            } else {
+                i += 1;
+                while i < chars.len() && chars[i].1 != c {
+                    if chars[i].1 == '\\' {
+                        i += 1;
+                    }
+                    i += 1;
+                }
+                i = (i + 1).min(chars.len());
+                TokenKind::Str
+            }
+        } else {
+            i += 1;
+            TokenKind::Punct
+        };
+
+        tokens.push(Token {
+            kind,
+            start: offset(start),
+            end: offset(i),
+        });
+    }
+
+    tokens
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn texts(line: &str) -> Vec<&str> {
+        tokenize(line).iter().map(|t| &line[t.start..t.end]).collect()
+    }
+
+    #[test]
+    fn test_tokenize() {
+        assert_eq!(
+            texts("let x = foo(12, \"a, b\");"),
+            vec!["let", "x", "=", "foo", "(", "12", ",", "\"a, b\"", ")", ";"]
+        );
+        assert_eq!(tokenize("x = 1.5")[2].kind, TokenKind::Number);
+    }
+
+    #[test]
+    fn test_tokenize_numbers() {
+        assert_eq!(texts("x = 1.5e3"), vec!["x", "=", "1.5e3"]);
+        assert_eq!(texts("for i in 0..10"), vec!["for", "i", "in", "0", ".", ".", "10"]);
+        assert_eq!(texts("2..=5"), vec!["2", ".", ".", "=", "5"]);
+        assert_eq!(texts("1.max(x)"), vec!["1", ".", "max", "(", "x", ")"]);
+        assert_eq!(texts("t.0.1"), vec!["t", ".", "0", ".", "1"]);
+    }
+
+    #[test]
+    fn test_tokenize_quotes() {
+        assert_eq!(texts("fn f<'a>(s: &'a str)")[3], "'");
+        assert_eq!(texts("c == '{'"), vec!["c", "=", "=", "'{'"]);
+        assert_eq!(texts("print('hi')"), vec!["print", "(", "'hi'", ")"]);
+        assert_eq!(texts("s = \"esc\\\"aped\""), vec!["s", "=", "\"esc\\\"aped\""]);
+    }
+}
@@ -0,0 +1,427 @@
+use crate::file_manager::FileManager;
+use crate::observer::{Control, Observer, Outcome};
+use crate::progress::{format_duration, ProgressTracker};
+use crate::strategy::Chomp;
+use anyhow::Result;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Gauge, List, ListItem, ListState, Paragraph, Sparkline};
+use ratatui::{DefaultTerminal, Frame};
+use std::collections::{BTreeMap, VecDeque};
+use std::ops::Range;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+/// Entries kept in the attempt log
+const LOG_SIZE: usize = 200;
+
+/// Command runs per sparkline bar
+const BATCH: usize = 10;
+
+/// Lines of the candidate's file captured for the source view, before and after the span
+const CONTEXT_BEFORE: usize = 8;
+const CONTEXT_AFTER: usize = 60;
+
+/// Redraws at most this often, except when paused
+const FRAME: Duration = Duration::from_millis(50);
+
+/// The candidate being tried and the source around it
+struct Focus {
+    file: PathBuf,
+    lines: Range<usize>,
+    /// Line numbers and text, captured before the candidate was applied
+    source: Vec<(usize, String)>,
+}
+
+/// Everything the terminal UI shows, kept apart from the terminal so it can be drawn anywhere
+struct State {
+    command: String,
+    /// Non-blank lines per file at the start, and now (None once deleted)
+    files: BTreeMap<PathBuf, (usize, Option<usize>)>,
+    initial_lines: usize,
+    current_lines: usize,
+    round: usize,
+    strategy: String,
+    progress: ProgressTracker,
+    focus: Option<Focus>,
+    /// Outcomes of attempts, None for warnings
+    log: VecDeque<(Option<Outcome>, String)>,
+    /// Percentage of kept candidates per batch of command runs
+    success_rates: Vec<u64>,
+    batch: (usize, usize),
+    paused: bool,
+}
+
+/// A ratatui dashboard of a run: files, the candidate being tried, the attempt log, the
+/// success rate and an ETA
+///
+/// Keys are read between candidates: `p` pauses, `s` skips the rest of the strategy, and `q`
+/// aborts, after which the run restores the original files.
+pub struct Tui {
+    terminal: DefaultTerminal,
+    state: State,
+    last_draw: Option<Instant>,
+}
+
+impl Tui {
+    /// Take over the terminal until the Tui is dropped
+    pub fn start(command: &str, file_manager: &FileManager) -> Result<Tui> {
+        let terminal = ratatui::try_init()?;
+        Ok(Tui { terminal, state: State::new(command, file_manager), last_draw: None })
+    }
+
+    fn draw(&mut self, force: bool) {
+        if !force && self.last_draw.is_some_and(|last| last.elapsed() < FRAME) {
+            return;
+        }
+        // A failed frame only loses a frame; the run goes on
+        let _ = self.terminal.draw(|frame| draw(frame, &self.state));
+        self.last_draw = Some(Instant::now());
+    }
+
+    /// Apply one key press, returning what it asks of the run
+    fn handle_key(&mut self, code: KeyCode, modifiers: KeyModifiers) -> Control {
+        match code {
+            KeyCode::Char('p') | KeyCode::Char(' ') => {
+                self.state.paused = !self.state.paused;
+                Control::Continue
+            }
+            KeyCode::Char('s') => Control::SkipStrategy,
+            KeyCode::Char('q') | KeyCode::Esc => Control::Abort,
+            KeyCode::Char('c') if modifiers.contains(KeyModifiers::CONTROL) => Control::Abort,
+            _ => Control::Continue,
+        }
+    }
+
+    fn next_key(&mut self, timeout: Duration) -> Option<(KeyCode, KeyModifiers)> {
+        if !event::poll(timeout).ok()? {
+            return None;
+        }
+        match event::read().ok()? {
+            Event::Key(key) if key.kind == KeyEventKind::Press => Some((key.code, key.modifiers)),
+            _ => None,
+        }
+    }
+}
+
+impl Drop for Tui {
+    fn drop(&mut self) {
+        ratatui::restore();
+    }
+}
+
+impl Observer for Tui {
+    fn round_started(&mut self, round: usize) {
+        self.state.round = round;
+    }
+
+    fn strategy_started(&mut self, name: &str, candidates: usize, _files: &FileManager) {
+        self.state.strategy = name.to_string();
+        self.state.progress = ProgressTracker::new(candidates);
+        self.draw(true);
+    }
+
+    fn attempt_started(&mut self, chomp: &Chomp, files: &FileManager) {
+        self.state.focus = focus(chomp, files);
+        self.draw(false);
+    }
+
+    fn attempt_finished(&mut self, chomp: &Chomp, outcome: Outcome, files: &FileManager) {
+        self.state.record(chomp, outcome, files);
+        self.draw(false);
+    }
+
+    fn warning(&mut self, message: &str) {
+        self.state.push_log(None, message.to_string());
+        self.draw(false);
+    }
+
+    fn control(&mut self) -> Control {
+        while let Some((code, modifiers)) = self.next_key(Duration::ZERO) {
+            let control = self.handle_key(code, modifiers);
+            if control != Control::Continue {
+                return control;
+            }
+        }
+
+        // Block until resumed; skipping and aborting work while paused too
+        while self.state.paused {
+            self.draw(true);
+            if let Some((code, modifiers)) = self.next_key(Duration::from_millis(250)) {
+                let control = self.handle_key(code, modifiers);
+                if control != Control::Continue {
+                    self.state.paused = false;
+                    return control;
+                }
+            }
+        }
+        self.draw(false);
+        Control::Continue
+    }
+}
+
+impl State {
+    fn new(command: &str, file_manager: &FileManager) -> State {
+        let files = file_manager
+            .files()
+            .iter()
+            .map(|(path, state)| (path.clone(), (state.non_blank_lines(), Some(state.non_blank_lines()))))
+            .collect();
+        State {
+            command: command.to_string(),
+            files,
+            initial_lines: file_manager.non_blank_lines(),
+            current_lines: file_manager.non_blank_lines(),
+            round: 0,
+            strategy: String::new(),
+            progress: ProgressTracker::new(0),
+            focus: None,
+            log: VecDeque::new(),
+            success_rates: Vec::new(),
+            batch: (0, 0),
+            paused: false,
+        }
+    }
+
+    fn record(&mut self, chomp: &Chomp, outcome: Outcome, files: &FileManager) {
+        self.progress.record_chomp(outcome == Outcome::Kept);
+        self.push_log(Some(outcome), format!("{}: {}", self.strategy, chomp));
+
+        // Skipped candidates never ran, so they don't count towards the success rate
+        if outcome != Outcome::Skipped {
+            self.batch.0 += 1;
+            if outcome == Outcome::Kept {
+                self.batch.1 += 1;
+            }
+            if self.batch.0 == BATCH {
+                self.success_rates.push((self.batch.1 * 100 / BATCH) as u64);
+                self.batch = (0, 0);
+            }
+        }
+
+        if outcome == Outcome::Kept {
+            for (path, (_, remaining)) in self.files.iter_mut() {
+                *remaining = files.get_file(path).filter(|state| !state.deleted).map(|state| state.non_blank_lines());
+            }
+            self.current_lines = files.non_blank_lines();
+        }
+    }
+
+    fn push_log(&mut self, outcome: Option<Outcome>, text: String) {
+        if self.log.len() == LOG_SIZE {
+            self.log.pop_front();
+        }
+        self.log.push_back((outcome, text));
+    }
+}
+
+/// Where a chomp acts, with the source around it
+fn focus(chomp: &Chomp, files: &FileManager) -> Option<Focus> {
+    let (file, lines) = match chomp {
+        Chomp::Range(range) => (&range.file, range.start_line..range.end_line),
+        Chomp::Edit(edit) => (&edit.file, edit.line..edit.line + 1),
+        Chomp::Replace(span) => (&span.file, span.start_line..span.end_line),
+        Chomp::DeleteFiles(paths) => {
+            let path = paths.first()?;
+            (path, 0..files.get_file(path)?.total_lines())
+        }
+        Chomp::Compound(parts) => return parts.iter().find_map(|part| focus(part, files)),
+    };
+    let state = files.get_file(file)?;
+    let first = lines.start.saturating_sub(CONTEXT_BEFORE);
+    let last = (lines.start + CONTEXT_AFTER).min(state.total_lines());
+    let source = (first..last).map(|line| (line, state.line(line).to_string())).collect();
+    Some(Focus { file: file.clone(), lines, source })
+}
+
+fn draw(frame: &mut Frame, state: &State) {
+    let [header, body, bottom, footer] =
+        Layout::vertical([Constraint::Length(3), Constraint::Min(8), Constraint::Length(10), Constraint::Length(1)])
+            .areas(frame.area());
+    let [files, source] = Layout::horizontal([Constraint::Percentage(30), Constraint::Percentage(70)]).areas(body);
+    let [log, rates] = Layout::horizontal([Constraint::Percentage(65), Constraint::Percentage(35)]).areas(bottom);
+
+    draw_header(frame, header, state);
+    draw_files(frame, files, state);
+    draw_source(frame, source, state);
+    draw_log(frame, log, state);
+
+    let sparkline = Sparkline::default()
+        .block(Block::bordered().title(format!("Success rate per {} runs", BATCH)))
+        .data(&state.success_rates)
+        .max(100)
+        .style(Style::default().fg(Color::Green));
+    frame.render_widget(sparkline, rates);
+
+    let mut keys = vec![Span::raw(" p pause · s skip strategy · q abort and restore")];
+    if state.paused {
+        keys.push(Span::styled("  ⏸ PAUSED", Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)));
+    }
+    frame.render_widget(Paragraph::new(Line::from(keys)), footer);
+}
+
+fn draw_header(frame: &mut Frame, area: Rect, state: &State) {
+    let removed = state.initial_lines.saturating_sub(state.current_lines);
+    let ratio = if state.initial_lines == 0 { 0.0 } else { removed as f64 / state.initial_lines as f64 };
+    let eta = match state.progress.eta() {
+        Some(eta) => format_duration(eta),
+        None => "-".to_string(),
+    };
+    let label = format!(
+        "{} → {} lines ({:.1}%) · round {} · {} {}/{} · ETA {}",
+        state.initial_lines,
+        state.current_lines,
+        ratio * 100.0,
+        state.round,
+        state.strategy,
+        state.progress.chomps_performed(),
+        state.progress.total_ranges(),
+        eta
+    );
+    let gauge = Gauge::default()
+        .block(Block::bordered().title(format!("🍴 chompie: {}", state.command)))
+        .gauge_style(Style::default().fg(Color::Green))
+        .ratio(ratio.clamp(0.0, 1.0))
+        .label(label);
+    frame.render_widget(gauge, area);
+}
+
+fn draw_files(frame: &mut Frame, area: Rect, state: &State) {
+    let current = state.focus.as_ref().map(|focus| &focus.file);
+    let mut selected = None;
+    let items: Vec<ListItem> = state
+        .files
+        .iter()
+        .enumerate()
+        .map(|(i, (path, (original, remaining)))| {
+            if Some(path) == current {
+                selected = Some(i);
+            }
+            let remaining = match remaining {
+                Some(remaining) => remaining.to_string(),
+                None => "deleted".to_string(),
+            };
+            ListItem::new(format!("{} {} → {}", path.display(), original, remaining))
+        })
+        .collect();
+    let list = List::new(items)
+        .block(Block::bordered().title(format!("Files ({})", state.files.len())))
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+    frame.render_stateful_widget(list, area, &mut ListState::default().with_selected(selected));
+}
+
+fn draw_source(frame: &mut Frame, area: Rect, state: &State) {
+    let Some(focus) = &state.focus else {
+        frame.render_widget(Paragraph::new("").block(Block::bordered().title("Candidate")), area);
+        return;
+    };
+    let highlight = Style::default().bg(Color::Red).fg(Color::White);
+    let lines: Vec<Line> = focus
+        .source
+        .iter()
+        .map(|(number, text)| {
+            let style = if focus.lines.contains(number) { highlight } else { Style::default() };
+            Line::from(vec![
+                Span::styled(format!("{:>5} ", number + 1), Style::default().fg(Color::DarkGray)),
+                Span::styled(text.clone(), style),
+            ])
+        })
+        .collect();
+    let title = format!(
+        "Candidate: {} lines {}-{}",
+        focus.file.display(),
+        focus.lines.start + 1,
+        focus.lines.end
+    );
+    frame.render_widget(Paragraph::new(lines).block(Block::bordered().title(title)), area);
+}
+
+fn draw_log(frame: &mut Frame, area: Rect, state: &State) {
+    let visible = area.height.saturating_sub(2) as usize;
+    let lines: Vec<Line> = state
+        .log
+        .iter()
+        .rev()
+        .take(visible)
+        .rev()
+        .map(|(outcome, text)| {
+            let (mark, color) = match outcome {
+                Some(Outcome::Kept) => ("✓", Color::Green),
+                Some(Outcome::Rejected) => ("✗", Color::Red),
+                Some(Outcome::Skipped) => ("·", Color::DarkGray),
+                None => ("!", Color::Yellow),
+            };
+            Line::from(vec![Span::styled(format!("{} ", mark), Style::default().fg(color)), Span::raw(text.clone())])
+        })
+        .collect();
+    frame.render_widget(Paragraph::new(lines).block(Block::bordered().title("Attempts")), area);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::strategy::ChompRange;
+    use ratatui::backend::TestBackend;
+    use ratatui::Terminal;
+
+    fn file_manager() -> FileManager {
+        let mut file_manager = FileManager::new();
+        file_manager.add_file_from_content(PathBuf::from("src/lib.rs"), "fn a() {}\nfn b() {}\nfn c() {}".to_string());
+        file_manager.add_file_from_content(PathBuf::from("src/main.rs"), "fn main() {}".to_string());
+        file_manager
+    }
+
+    fn rendered(state: &State) -> String {
+        let mut terminal = Terminal::new(TestBackend::new(100, 30)).unwrap();
+        terminal.draw(|frame| draw(frame, state)).unwrap();
+        let buffer = terminal.backend().buffer();
+        (0..buffer.area.height)
+            .map(|y| (0..buffer.area.width).map(|x| buffer[(x, y)].symbol()).collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn test_focus() {
+        let files = file_manager();
+        let range = Chomp::Range(ChompRange { file: PathBuf::from("src/lib.rs"), start_line: 1, end_line: 2 });
+        let focus = focus(&range, &files).unwrap();
+        assert_eq!(focus.lines, 1..2);
+        assert_eq!(focus.source[1], (1, "fn b() {}".to_string()));
+
+        let delete = Chomp::DeleteFiles(vec![PathBuf::from("src/main.rs")]);
+        assert_eq!(super::focus(&delete, &files).unwrap().lines, 0..1);
+        assert!(super::focus(&Chomp::DeleteFiles(vec![PathBuf::from("gone.rs")]), &files).is_none());
+    }
+
+    #[test]
+    fn test_draw() {
+        let mut files = file_manager();
+        let mut state = State::new("cargo test", &files);
+        state.round = 2;
+        state.strategy = "bisection".to_string();
+        state.progress = ProgressTracker::new(4);
+
+        let chomp = Chomp::Range(ChompRange { file: PathBuf::from("src/lib.rs"), start_line: 1, end_line: 2 });
+        state.focus = focus(&chomp, &files);
+        files.get_file_mut(std::path::Path::new("src/lib.rs")).unwrap().blank_lines(&[1]);
+        state.record(&chomp, Outcome::Kept, &files);
+
+        let screen = rendered(&state);
+        assert!(screen.contains("chompie: cargo test"), "{}", screen);
+        assert!(screen.contains("4 → 3 lines (25.0%) · round 2 · bisection 1/4"), "{}", screen);
+        assert!(screen.contains("src/lib.rs 3 → 2"), "{}", screen);
+        assert!(screen.contains("Candidate: src/lib.rs lines 2-2"), "{}", screen);
+        assert!(screen.contains("✓ bisection:"), "{}", screen);
+        assert!(!screen.contains("PAUSED"));
+
+        // Warnings land in the log instead of on stderr, under the screen
+        state.push_log(None, "Error during chomp: no such directory".to_string());
+        assert!(rendered(&state).contains("! Error during chomp: no such directory"));
+
+        state.paused = true;
+        assert!(rendered(&state).contains("PAUSED"));
+    }
+}